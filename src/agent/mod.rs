@@ -1,5 +1,6 @@
-use crate::llm::{create_client, LlmClient, Message};
+use crate::llm::{create_client, LlmClient, Message, MessageContent};
 use crate::tools::ToolRegistry;
+use crate::utils::{select_regression_tests, BatchRunner, RepoPool};
 use crate::DgmResult;
 use anyhow::Context;
 use regex::Regex;
@@ -16,6 +17,11 @@ struct ToolUse {
     tool_input: Value,
 }
 
+/// Upper bound on how many `<tool_use>` blocks a single LLM turn may fan out to the worker
+/// pool. Kept separate from `max_iterations` in [`AgenticSystem::forward`] so one turn reading
+/// several files doesn't itself count as several iterations of the conversation loop.
+const MAX_TOOL_CALLS_PER_TURN: usize = 8;
+
 pub struct AgenticSystem {
     problem_statement: String,
     git_tempdir: PathBuf,
@@ -25,6 +31,13 @@ pub struct AgenticSystem {
     instance_id: String,
     llm_client: Box<dyn LlmClient + Send + Sync>,
     tool_registry: Arc<ToolRegistry>,
+    /// Caches the opened repo handle and recent base-commit diffs (time-bounded, keyed by
+    /// `git_tempdir` + `base_commit`) so `get_current_edits`/`run_regression_tests` don't re-fork
+    /// a `git diff` subprocess on every call within the same iteration.
+    repo_pool: RepoPool,
+    /// Opt-in: append a syntax-highlighted HTML rendering of the diff to the chat history file
+    /// alongside the plain-text log. See [`crate::config::DgmConfig::log_html_diff`].
+    log_html_diff: bool,
 }
 
 impl AgenticSystem {
@@ -46,7 +59,14 @@ impl AgenticSystem {
         };
 
         let llm_client = create_client(model)?;
-        let tool_registry = Arc::new(ToolRegistry::new());
+        // Confine the `editor` tool to the repo's working tree, so a self-improving agent can't
+        // edit files outside the workspace it was given.
+        let mut tool_registry = ToolRegistry::with_sandbox_root(&git_tempdir)?;
+        // Layer in any domain-specific tools (linter, type checker, ...) the user declared in
+        // config, so they show up alongside `bash`/`editor` without editing this crate.
+        let config = crate::config::DgmConfig::load()?;
+        tool_registry.register_external_tools(config.extra_tools);
+        let tool_registry = Arc::new(tool_registry);
 
         // Clear the chat history file
         if let Some(parent) = chat_history_file.parent() {
@@ -63,10 +83,15 @@ impl AgenticSystem {
             instance_id,
             llm_client,
             tool_registry,
+            repo_pool: RepoPool::default(),
+            log_html_diff: config.log_html_diff,
         })
     }
 
-    pub async fn forward(&self) -> DgmResult<()> {
+    /// Run the conversation loop to completion, returning how many iterations it took so
+    /// callers benchmarking the agent (see `evaluation::bench`) can record it alongside timing
+    /// and diff size.
+    pub async fn forward(&self) -> DgmResult<u32> {
         info!("Starting agentic system for instance: {}", self.instance_id);
 
         let instruction = self.build_instruction();
@@ -76,6 +101,7 @@ impl AgenticSystem {
         let mut current_message = instruction;
         let max_iterations = 50; // Prevent infinite loops
         let mut iteration = 0;
+        let tool_infos = self.tool_registry.list_tools();
 
         loop {
             iteration += 1;
@@ -84,32 +110,61 @@ impl AgenticSystem {
                 break;
             }
 
-            // Send message to LLM
+            // Send message to LLM, offering our tools natively so providers that support it
+            // (Anthropic `tool_use`, OpenAI `tool_calls`) can return structured calls instead of
+            // us having to scrape them back out of the response text.
             let response = self
                 .llm_client
-                .send_message(&current_message, "", Some(message_history.clone()), 0.7)
+                .send_message(&current_message, "", Some(message_history.clone()), 0.7, Some(&tool_infos))
                 .await?;
 
             message_history = response.message_history;
 
-            // Check for tool use in the response
-            if let Some(tool_use) = self.check_for_tool_use(&response.content).await? {
-                // Execute the tool
-                let tool_result = self.execute_tool(&tool_use).await?;
-
-                // Prepare the tool result message for the next iteration
-                current_message = format!(
-                    "Tool Used: {}\nTool Input: {:?}\nTool Result: {}",
-                    tool_use.tool_name, tool_use.tool_input, tool_result
-                );
-
-                // Log tool usage
-                self.log_tool_usage(&tool_use, &tool_result).await?;
+            // Prefer the provider's native tool calls when it returned any; fall back to
+            // scraping `<tool_use>` tags out of the response text for models/backends that
+            // don't support native tool calling. A turn may contain several independent calls
+            // (e.g. a handful of file reads); dispatch them all to the worker pool at once
+            // instead of spending a full round-trip per call.
+            let tool_uses = if !response.tool_calls.is_empty() {
+                response
+                    .tool_calls
+                    .iter()
+                    .map(|tc| ToolUse {
+                        tool_name: tc.tool_name.clone(),
+                        tool_input: tc.tool_input.clone(),
+                    })
+                    .collect()
             } else {
+                self.check_for_tool_use(&response.content).await?
+            };
+            if tool_uses.is_empty() {
                 // No tool use detected, conversation is complete
                 info!("No tool use detected, conversation complete");
                 break;
             }
+
+            let results = self.execute_tool_calls(tool_uses.clone()).await;
+
+            // Any of the tools just run (edit, bash, ...) may have changed the working tree, so
+            // the pool's memoized diff for `base_commit` is now stale; drop it rather than
+            // letting `get_current_edits` keep serving a snapshot that omits these edits for the
+            // rest of `diff_ttl`.
+            self.repo_pool.invalidate_repo(&self.git_tempdir);
+
+            // `BatchRunner::run` preserves input order, so zipping back with `tool_uses` keeps
+            // each result lined up with the call that produced it.
+            let mut message_parts = Vec::with_capacity(tool_uses.len());
+            for (tool_use, result) in tool_uses.iter().zip(results.into_iter()) {
+                let tool_result = result?;
+
+                message_parts.push(format!(
+                    "Tool Used: {}\nTool Input: {:?}\nTool Result: {}",
+                    tool_use.tool_name, tool_use.tool_input, tool_result
+                ));
+
+                self.log_tool_usage(tool_use, &tool_result).await?;
+            }
+            current_message = message_parts.join("\n\n");
         }
 
         // Log the final conversation
@@ -117,7 +172,8 @@ impl AgenticSystem {
 
         info!("Agentic system completed for instance: {} after {} iterations",
               self.instance_id, iteration);
-        Ok(())
+        // `iteration` overshoots by one when the cap above stopped the loop.
+        Ok(iteration.min(max_iterations))
     }
 
     fn build_instruction(&self) -> String {
@@ -159,45 +215,93 @@ impl AgenticSystem {
         for message in message_history {
             log_content.push_str(&format!("## {}\n\n", message.role.to_uppercase()));
 
-            // Extract text content from the message
-            let content = if let Some(text) = message.content.as_str() {
-                text.to_string()
-            } else if let Some(array) = message.content.as_array() {
-                array
-                    .iter()
-                    .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            } else {
-                message.content.to_string()
+            // Render the message's typed content explicitly instead of just extracting "text"
+            // fields, so tool calls/results show up in the log instead of as raw JSON.
+            let content = match message.content_view() {
+                MessageContent::Text { text } => text,
+                MessageContent::ToolCall { id, tool_name, tool_input } => format!(
+                    "**Tool Call** `{}` (id: `{}`)\n```json\n{}\n```",
+                    tool_name,
+                    id,
+                    serde_json::to_string_pretty(&tool_input).unwrap_or_default()
+                ),
+                MessageContent::ToolResult { tool_call_id, content } => format!(
+                    "**Tool Result** (id: `{}`)\n```\n{}\n```",
+                    tool_call_id, content
+                ),
             };
 
             log_content.push_str(&content);
             log_content.push_str("\n\n---\n\n");
         }
 
+        if self.log_html_diff {
+            if let Ok(diff) = self.repo_pool.get_repo(&self.git_tempdir)
+                .and_then(|repo| repo.diff_versus_commit(&self.base_commit))
+            {
+                log_content.push_str("## DIFF\n\n");
+                log_content.push_str(&diff.render_html());
+                log_content.push_str("\n\n---\n\n");
+            }
+        }
+
         fs::write(&self.chat_history_file, log_content).await?;
         Ok(())
     }
 
+    /// Diff the working tree against `base_commit`, via [`RepoPool::cached_diff`] so the
+    /// repository is opened once and the rendered diff is memoized for a short time instead of
+    /// forking a `git diff` subprocess on every call.
     pub async fn get_current_edits(&self) -> DgmResult<String> {
-        // Use git diff to get current changes
-        let output = tokio::process::Command::new("git")
-            .arg("diff")
-            .arg(&self.base_commit)
-            .current_dir(&self.git_tempdir)
-            .output()
-            .await?;
+        Ok(self.repo_pool.cached_diff(&self.git_tempdir, &self.base_commit)?.diff)
+    }
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Git diff failed: {}", error));
+    /// Identify regression tests to run, preferring a deterministic static dependency-graph
+    /// analysis (see [`crate::utils::select_regression_tests`]) over an LLM round-trip: changed
+    /// files are walked backwards through a Python import graph to every test transitively
+    /// depending on them, which is reproducible and far cheaper than asking the model to guess.
+    /// Falls back to the original LLM-driven prompt when the analysis errors out or the diff
+    /// doesn't map to any known module (e.g. the change is to non-Python files).
+    pub async fn get_regression_tests(&self) -> DgmResult<String> {
+        if let Some(summary) = self.select_regression_tests_statically().await {
+            return Ok(summary);
         }
 
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        self.get_regression_tests_via_llm().await
     }
 
-    pub async fn get_regression_tests(&self) -> DgmResult<String> {
+    /// Static half of [`Self::get_regression_tests`]. Returns `None` (rather than an error) on
+    /// any failure to parse or select, so the caller falls through to the LLM path instead of
+    /// surfacing an analyzer bug as a hard failure of the whole instance.
+    async fn select_regression_tests_statically(&self) -> Option<String> {
+        let diff = self.repo_pool.get_repo(&self.git_tempdir).ok()?.diff_versus_commit(&self.base_commit).ok()?;
+        let changed_files: Vec<String> = diff
+            .files
+            .iter()
+            .filter_map(|f| f.new_path.clone().or_else(|| f.old_path.clone()))
+            .collect();
+        if changed_files.is_empty() {
+            return None;
+        }
+
+        match select_regression_tests(&self.git_tempdir, &changed_files) {
+            Ok(selection) if !selection.is_empty() => {
+                debug!(
+                    "Statically selected {} regression test file(s) for instance {}",
+                    selection.tests.len(),
+                    self.instance_id
+                );
+                Some(selection.render_summary())
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Static regression test selection failed, falling back to LLM: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn get_regression_tests_via_llm(&self) -> DgmResult<String> {
         let instruction = format!(
             "I have uploaded a Python code repository in the directory {}.\n\n<problem_description>\n{}\n</problem_description>\n\n",
             self.git_tempdir.display(),
@@ -218,7 +322,7 @@ impl AgenticSystem {
 
         let response = self
             .llm_client
-            .send_message(&instruction, "", None, 0.7)
+            .send_message(&instruction, "", None, 0.7, None)
             .await?;
 
         Ok(response.content)
@@ -250,51 +354,47 @@ impl AgenticSystem {
 
         let response = self
             .llm_client
-            .send_message(&instruction, "", None, 0.7)
+            .send_message(&instruction, "", None, 0.7, None)
             .await?;
 
         Ok(response.content)
     }
 
-    /// Check if the response contains tool use
-    async fn check_for_tool_use(&self, response: &str) -> DgmResult<Option<ToolUse>> {
+    /// Fallback for models/backends without native tool-calling support: find every
+    /// `<tool_use>` block in the response text, in order, up to [`MAX_TOOL_CALLS_PER_TURN`].
+    async fn check_for_tool_use(&self, response: &str) -> DgmResult<Vec<ToolUse>> {
         // Look for <tool_use> tags in the response (for models without built-in tool calling)
         let pattern = r"<tool_use>(.*?)</tool_use>";
         let re = Regex::new(pattern).context("Failed to compile regex")?;
 
-        if let Some(captures) = re.captures(response) {
-            if let Some(tool_use_str) = captures.get(1) {
-                let tool_use_str = tool_use_str.as_str().trim();
-
-                // Try to parse the tool use as JSON
-                if let Ok(tool_use_json) = serde_json::from_str::<Value>(tool_use_str) {
-                    if let (Some(tool_name), Some(tool_input)) = (
-                        tool_use_json.get("tool_name").and_then(|v| v.as_str()),
-                        tool_use_json.get("tool_input")
-                    ) {
-                        return Ok(Some(ToolUse {
-                            tool_name: tool_name.to_string(),
-                            tool_input: tool_input.clone(),
-                        }));
-                    }
-                }
+        let mut tool_uses = Vec::new();
+        for captures in re.captures_iter(response).take(MAX_TOOL_CALLS_PER_TURN) {
+            let Some(tool_use_str) = captures.get(1) else {
+                continue;
+            };
+            let tool_use_str = tool_use_str.as_str().trim();
 
-                // Try to parse as Python dict-like format
-                if let Ok(parsed) = self.parse_python_dict(tool_use_str) {
-                    if let (Some(tool_name), Some(tool_input)) = (
-                        parsed.get("tool_name").and_then(|v| v.as_str()),
-                        parsed.get("tool_input")
-                    ) {
-                        return Ok(Some(ToolUse {
-                            tool_name: tool_name.to_string(),
-                            tool_input: tool_input.clone(),
-                        }));
-                    }
-                }
+            // Try to parse the tool use as JSON, falling back to Python dict-like format.
+            let parsed = serde_json::from_str::<Value>(tool_use_str)
+                .ok()
+                .or_else(|| self.parse_python_dict(tool_use_str).ok());
+
+            let Some(parsed) = parsed else {
+                continue;
+            };
+
+            if let (Some(tool_name), Some(tool_input)) = (
+                parsed.get("tool_name").and_then(|v| v.as_str()),
+                parsed.get("tool_input"),
+            ) {
+                tool_uses.push(ToolUse {
+                    tool_name: tool_name.to_string(),
+                    tool_input: tool_input.clone(),
+                });
             }
         }
 
-        Ok(None)
+        Ok(tool_uses)
     }
 
     /// Parse Python dict-like string to JSON Value
@@ -309,17 +409,30 @@ impl AgenticSystem {
         serde_json::from_str(&json_str)
     }
 
-    /// Execute a tool with the given input
-    async fn execute_tool(&self, tool_use: &ToolUse) -> DgmResult<String> {
-        debug!("Executing tool: {} with input: {:?}", tool_use.tool_name, tool_use.tool_input);
-
-        let result = self.tool_registry
-            .execute_tool(&tool_use.tool_name, tool_use.tool_input.clone())
+    /// Execute several tool calls concurrently through a CPU-count-bounded worker pool,
+    /// returning one result per input in the same order, so a turn that fans out to several
+    /// independent reads doesn't serialize them one round-trip at a time.
+    async fn execute_tool_calls(&self, tool_uses: Vec<ToolUse>) -> Vec<DgmResult<String>> {
+        let registry = self.tool_registry.clone();
+
+        BatchRunner::new(None)
+            .run(tool_uses, move |tool_use| {
+                let registry = registry.clone();
+                async move {
+                    debug!("Executing tool: {} with input: {:?}", tool_use.tool_name, tool_use.tool_input);
+
+                    let result = registry
+                        .execute_tool(&tool_use.tool_name, tool_use.tool_input.clone())
+                        .await
+                        .with_context(|| format!("Failed to execute tool '{}'", tool_use.tool_name));
+
+                    if let Ok(ref output) = result {
+                        debug!("Tool '{}' result: {}", tool_use.tool_name, output);
+                    }
+                    result
+                }
+            })
             .await
-            .with_context(|| format!("Failed to execute tool '{}'", tool_use.tool_name))?;
-
-        debug!("Tool '{}' result: {}", tool_use.tool_name, result);
-        Ok(result)
     }
 
     /// Log tool usage to the chat history file
@@ -359,6 +472,10 @@ impl AgenticSystem {
 
         prompt.push_str("Use the available tools in this format:\n");
         prompt.push_str("```\n<tool_use>\n{\n    \"tool_name\": \"tool_name_here\",\n    \"tool_input\": {\n        \"parameter\": \"value\"\n    }\n}\n</tool_use>\n```\n\n");
+        prompt.push_str(&format!(
+            "You may emit up to {} independent `<tool_use>` blocks in a single response; they will be run concurrently and their results returned together, in the order you emitted them.\n\n",
+            MAX_TOOL_CALLS_PER_TURN
+        ));
 
         prompt
     }
@@ -77,8 +77,8 @@ async fn main() -> DgmResult<()> {
 
     // Get code diff and save to model_patch.diff
     let git_manager = GitManager::new(&cli.git_dir)?;
-    let model_patch = git_manager.diff_versus_commit(&cli.base_commit)?;
-    
+    let model_patch = git_manager.diff_versus_commit(&cli.base_commit)?.render();
+
     let model_patch_outfile = cli.outdir.join("model_patch.diff");
     
     // Ensure output directory exists
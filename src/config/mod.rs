@@ -1,3 +1,4 @@
+use crate::tools::external::ExternalToolSpec;
 use crate::{DgmResult, Deserialize, Serialize};
 use std::env;
 
@@ -15,6 +16,26 @@ pub struct ApiConfig {
 pub struct DockerConfig {
     pub image_name: String,
     pub timeout_seconds: u64,
+    /// Docker daemons evaluation containers may be scheduled against. Defaults to a single
+    /// local-socket endpoint; add more entries (each an independent `tcp://host:port` daemon)
+    /// to fan a large evaluation run out across a build farm instead of one machine.
+    pub endpoints: Vec<DockerEndpointSpec>,
+}
+
+/// One Docker daemon `EvaluationHarness` may submit containers to, as plain config data — see
+/// `utils::docker_scheduler::DockerEndpointConfig` for the connected form used at runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerEndpointSpec {
+    pub name: String,
+    /// Docker daemon URI, e.g. `tcp://build-host:2376`. `None` means the local socket.
+    pub address: Option<String>,
+    /// Maximum number of containers this endpoint may run at once.
+    pub num_max_jobs: u32,
+    /// Minimum acceptable Docker API version for this endpoint (e.g. "1.41"). `EvaluationHarness::new`
+    /// verifies every endpoint against its own constraint before any work starts, failing fast on
+    /// a stale or incompatible daemon instead of surfacing a cryptic error deep into a run.
+    /// `None` skips the check.
+    pub min_api_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +43,25 @@ pub struct EvaluationConfig {
     pub max_workers: u32,
     pub timeout_seconds: u64,
     pub num_evals_parallel: u32,
+    /// Address `EvaluationHarness::new` binds an admin HTTP server to (e.g. "127.0.0.1:9898"),
+    /// serving live progress at `/status` (JSON) and `/metrics` (Prometheus text) so a long
+    /// SWE-bench/Polyglot sweep can be scraped or dashboarded instead of only tailing logs.
+    /// `None` skips starting the server.
+    pub admin_bind_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BashConfig {
+    /// RLIMIT_CPU applied to spawned bash commands, in seconds.
+    pub cpu_seconds: u64,
+    /// RLIMIT_AS applied to spawned bash commands, in bytes.
+    pub memory_bytes: u64,
+    /// RLIMIT_FSIZE applied to spawned bash commands, in bytes.
+    pub file_size_bytes: u64,
+    /// RLIMIT_NPROC applied to spawned bash commands, guarding against fork bombs.
+    pub max_processes: u64,
+    /// Cap on the accumulated command output retained in memory before truncation.
+    pub output_cap_bytes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +69,18 @@ pub struct DgmConfig {
     pub api: ApiConfig,
     pub docker: DockerConfig,
     pub evaluation: EvaluationConfig,
+    pub bash: BashConfig,
+    /// Domain-specific tools (a linter, a type checker, a search index, ...) a user wants to
+    /// expose to the agent without editing this crate. Registered onto a [`crate::tools::ToolRegistry`]
+    /// via `register_external_tools`.
+    #[serde(default)]
+    pub extra_tools: Vec<ExternalToolSpec>,
+    /// When true, `AgenticSystem::log_conversation` appends a syntax-highlighted HTML rendering
+    /// of the current diff (see `utils::git::WorkdirDiff::render_html`) to the chat history file,
+    /// alongside the plain-text conversation log. Off by default since most chat history
+    /// consumers just want plain text.
+    #[serde(default)]
+    pub log_html_diff: bool,
 }
 
 impl Default for ApiConfig {
@@ -49,6 +101,12 @@ impl Default for DockerConfig {
         Self {
             image_name: "dgm".to_string(),
             timeout_seconds: 1800, // 30 minutes
+            endpoints: vec![DockerEndpointSpec {
+                name: "local".to_string(),
+                address: None,
+                num_max_jobs: 5,
+                min_api_version: None,
+            }],
         }
     }
 }
@@ -59,6 +117,19 @@ impl Default for EvaluationConfig {
             max_workers: 5,
             timeout_seconds: 3600, // 1 hour
             num_evals_parallel: 5,
+            admin_bind_address: None,
+        }
+    }
+}
+
+impl Default for BashConfig {
+    fn default() -> Self {
+        Self {
+            cpu_seconds: 300,
+            memory_bytes: 4 * 1024 * 1024 * 1024,   // 4 GiB
+            file_size_bytes: 1024 * 1024 * 1024,    // 1 GiB
+            max_processes: 256,
+            output_cap_bytes: 1024 * 1024,          // 1 MiB
         }
     }
 }
@@ -69,6 +140,9 @@ impl Default for DgmConfig {
             api: ApiConfig::default(),
             docker: DockerConfig::default(),
             evaluation: EvaluationConfig::default(),
+            bash: BashConfig::default(),
+            extra_tools: Vec::new(),
+            log_html_diff: false,
         }
     }
 }
@@ -1,20 +1,68 @@
 use crate::{
-    dgm::{Archive, SelfImproveEntry},
+    dgm::{Archive, LineageIndex, SelfImproveEntry},
     utils::{load_json_file, EvaluationResult},
-    DgmResult,
+    DgmResult, Serialize,
 };
-use rand::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
 use tracing::{debug, info};
 
+/// Per-criterion exponents `choose_parents`'s `"wpm"` method raises each candidate's normalized
+/// criterion to, before taking their product. Higher means that criterion dominates the combined
+/// weight; `1.0` (the default for all four) weighs them equally, i.e. a plain product of the
+/// normalized criteria.
+#[derive(Debug, Clone, Copy)]
+pub struct WpmWeights {
+    pub accuracy: f64,
+    pub novelty: f64,
+    pub resolved_ratio: f64,
+    pub emptypatch_penalty: f64,
+}
+
+impl Default for WpmWeights {
+    fn default() -> Self {
+        Self {
+            accuracy: 1.0,
+            novelty: 1.0,
+            resolved_ratio: 1.0,
+            emptypatch_penalty: 1.0,
+        }
+    }
+}
+
+/// Floor a normalized `"wpm"` criterion value is clamped to before exponentiation, so a candidate
+/// that scores zero on one criterion doesn't annihilate its entire combined weight (and become
+/// permanently unselectable) via a zero in the product.
+const WPM_EPSILON: f64 = 1e-6;
+
 pub struct EvolutionStrategy {
     method: String,
+    wpm_weights: WpmWeights,
+    rng: RefCell<ChaCha8Rng>,
 }
 
 impl EvolutionStrategy {
-    pub fn new(method: String) -> Self {
-        Self { method }
+    /// `seed` fixes every downstream random choice (which parents, which entry ids) this strategy
+    /// makes, so a problematic generation can be replayed exactly. When `None`, a seed is drawn
+    /// from entropy and logged so the run can still be reproduced later from the log.
+    pub fn new(method: String, seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+        info!("EvolutionStrategy seeded with {seed} (pass this seed to replay this run's selections)");
+
+        Self {
+            method,
+            wpm_weights: WpmWeights::default(),
+            rng: RefCell::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Override the per-criterion weights the `"wpm"` method combines candidates with.
+    pub fn with_wpm_weights(mut self, wpm_weights: WpmWeights) -> Self {
+        self.wpm_weights = wpm_weights;
+        self
     }
 
     /// Choose self-improvement entries for the current generation
@@ -30,25 +78,32 @@ impl EvolutionStrategy {
 
         // Get parent candidates with their performance metrics
         let candidates = self.get_candidates(archive, output_dir)?;
-        
+
         if candidates.is_empty() {
             return Ok(entries);
         }
 
+        // Index the archive's lineage once, so parent selection and entry selection can reason
+        // about ancestry (generation depth, descendant fan-out, ancestor failure chains) without
+        // repeatedly re-scanning every commit's metadata.json.
+        let lineage = LineageIndex::build(archive, output_dir)?;
+
         // Choose parents based on method and baseline
         let parent_commits = match run_baseline {
             Some("no_darwin") => {
-                // Always take the last commit
-                let commits: Vec<String> = candidates.keys().cloned().collect();
+                // Always take the last commit, deterministically ordered (candidates is a
+                // HashMap, so its iteration order is not stable across processes).
+                let mut commits: Vec<String> = candidates.keys().cloned().collect();
+                commits.sort();
                 vec![commits.last().unwrap().clone(); selfimprove_size as usize]
             }
-            _ => self.choose_parents(&candidates, selfimprove_size)?,
+            _ => self.choose_parents(&candidates, &lineage, selfimprove_size)?,
         };
 
         // Choose entries for each parent
         for parent_commit in parent_commits {
             if let Some(candidate) = candidates.get(&parent_commit) {
-                let entry = self.choose_entry_for_parent(candidate, polyglot)?;
+                let entry = self.choose_entry_for_parent(candidate, &candidates, &lineage, &parent_commit, polyglot)?;
                 if let Some(entry) = entry {
                     entries.push(SelfImproveEntry::new(parent_commit, entry));
                 }
@@ -117,10 +172,14 @@ impl EvolutionStrategy {
     fn choose_parents(
         &self,
         candidates: &HashMap<String, CandidateInfo>,
+        lineage: &LineageIndex,
         selfimprove_size: u32,
     ) -> DgmResult<Vec<String>> {
-        let commits: Vec<String> = candidates.keys().cloned().collect();
-        let mut rng = thread_rng();
+        // `candidates` is a HashMap, so its iteration order varies across processes; sort the
+        // commits so the same seed maps onto the same candidate order (and thus the same draws)
+        // on every run.
+        let mut commits: Vec<String> = candidates.keys().cloned().collect();
+        commits.sort();
 
         match self.method.as_str() {
             "score_prop" => {
@@ -133,7 +192,7 @@ impl EvolutionStrategy {
                 let total_score: f64 = scores.iter().sum();
                 let probabilities: Vec<f64> = scores.iter().map(|s| s / total_score).collect();
 
-                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size, &mut rng))
+                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size))
             }
             "score_child_prop" => {
                 let scores: Vec<f64> = commits
@@ -156,7 +215,71 @@ impl EvolutionStrategy {
                 let total_score: f64 = combined_scores.iter().sum();
                 let probabilities: Vec<f64> = combined_scores.iter().map(|s| s / total_score).collect();
 
-                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size, &mut rng))
+                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size))
+            }
+            "wpm" => {
+                // Each candidate's total known outcomes, for the ratio/penalty criteria below.
+                let totals: Vec<usize> = commits
+                    .iter()
+                    .map(|c| {
+                        let candidate = &candidates[c];
+                        candidate.total_resolved_ids.len() + candidate.total_unresolved_ids.len() + candidate.total_emptypatch_ids.len()
+                    })
+                    .collect();
+
+                let accuracy: Vec<f64> = commits.iter().map(|c| candidates[c].accuracy_score).collect();
+                let novelty: Vec<f64> = commits.iter().map(|c| 1.0 / (1.0 + candidates[c].children_count as f64)).collect();
+                let resolved_ratio: Vec<f64> = commits
+                    .iter()
+                    .zip(&totals)
+                    .map(|(c, &total)| if total == 0 { 0.0 } else { candidates[c].total_resolved_ids.len() as f64 / total as f64 })
+                    .collect();
+                let emptypatch_penalty: Vec<f64> = commits
+                    .iter()
+                    .zip(&totals)
+                    .map(|(c, &total)| if total == 0 { 1.0 } else { 1.0 - candidates[c].total_emptypatch_ids.len() as f64 / total as f64 })
+                    .collect();
+
+                let criteria: [(&Vec<f64>, f64); 4] = [
+                    (&accuracy, self.wpm_weights.accuracy),
+                    (&novelty, self.wpm_weights.novelty),
+                    (&resolved_ratio, self.wpm_weights.resolved_ratio),
+                    (&emptypatch_penalty, self.wpm_weights.emptypatch_penalty),
+                ];
+
+                // Weighted product model: normalize each criterion to (0, 1] across the candidate
+                // set, then combine them as `product(v_j ^ w_j)` per candidate.
+                let combined_weights: Vec<f64> = (0..commits.len())
+                    .map(|i| {
+                        criteria.iter().fold(1.0, |product, (values, weight)| {
+                            let max = values.iter().cloned().fold(0.0f64, f64::max);
+                            let normalized = if max <= 0.0 { WPM_EPSILON } else { (values[i] / max).max(WPM_EPSILON) };
+                            product * normalized.powf(*weight)
+                        })
+                    })
+                    .collect();
+
+                let total_weight: f64 = combined_weights.iter().sum();
+                let probabilities: Vec<f64> = combined_weights.iter().map(|w| w / total_weight).collect();
+
+                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size))
+            }
+            "novelty_depth" => {
+                // Bias toward commits on shallower or less-explored branches: fewer descendants
+                // already sampled from them, and a shorter ancestor chain back to "initial".
+                let weights: Vec<f64> = commits
+                    .iter()
+                    .map(|c| {
+                        let generation = lineage.generation(c).unwrap_or(0) as f64;
+                        let descendant_count = lineage.descendants(c).len() as f64;
+                        1.0 / (1.0 + generation) * 1.0 / (1.0 + descendant_count)
+                    })
+                    .collect();
+
+                let total_weight: f64 = weights.iter().sum();
+                let probabilities: Vec<f64> = weights.iter().map(|w| w / total_weight).collect();
+
+                Ok(self.weighted_sample(&commits, &probabilities, selfimprove_size))
             }
             "best" => {
                 let mut sorted_commits = commits.clone();
@@ -171,7 +294,8 @@ impl EvolutionStrategy {
 
                 // Fill remaining slots with random selection from the best ones
                 while result.len() < selfimprove_size as usize {
-                    result.push(sorted_commits[rng.gen_range(0..selected_count)].clone());
+                    let pick = self.rng.borrow_mut().gen_range(0..selected_count);
+                    result.push(sorted_commits[pick].clone());
                 }
 
                 Ok(result)
@@ -179,44 +303,53 @@ impl EvolutionStrategy {
             _ => {
                 // Random selection
                 Ok((0..selfimprove_size)
-                    .map(|_| commits[rng.gen_range(0..commits.len())].clone())
+                    .map(|_| {
+                        let pick = self.rng.borrow_mut().gen_range(0..commits.len());
+                        commits[pick].clone()
+                    })
                     .collect())
             }
         }
     }
 
-    fn weighted_sample(
-        &self,
-        items: &[String],
-        weights: &[f64],
-        count: u32,
-        rng: &mut ThreadRng,
-    ) -> Vec<String> {
+    fn weighted_sample(&self, items: &[String], weights: &[f64], count: u32) -> Vec<String> {
         let mut result = Vec::new();
-        
+
         for _ in 0..count {
-            let r: f64 = rng.gen();
+            let r: f64 = self.rng.borrow_mut().gen();
             let mut cumulative = 0.0;
-            
+            let mut picked = false;
+
             for (i, &weight) in weights.iter().enumerate() {
                 cumulative += weight;
                 if r <= cumulative {
                     result.push(items[i].clone());
+                    picked = true;
                     break;
                 }
             }
+
+            // Floating-point error can leave `cumulative` just under 1.0, so a draw this close
+            // to 1.0 falls through every bucket above; fall back to the last item rather than
+            // silently returning fewer than `count` picks.
+            if !picked {
+                if let Some(last) = items.last() {
+                    result.push(last.clone());
+                }
+            }
         }
-        
+
         result
     }
 
     fn choose_entry_for_parent(
         &self,
         candidate: &CandidateInfo,
+        candidates: &HashMap<String, CandidateInfo>,
+        lineage: &LineageIndex,
+        commit: &str,
         polyglot: bool,
     ) -> DgmResult<Option<String>> {
-        let mut rng = thread_rng();
-
         if polyglot {
             let entry_ids = [
                 candidate.total_emptypatch_ids.clone(),
@@ -235,10 +368,12 @@ impl EvolutionStrategy {
                 if all_ids.is_empty() {
                     return Ok(None);
                 }
-                return Ok(Some(all_ids[rng.gen_range(0..all_ids.len())].clone()));
+                let pick = self.rng.borrow_mut().gen_range(0..all_ids.len());
+                return Ok(Some(all_ids[pick].clone()));
             }
 
-            return Ok(Some(entry_ids[rng.gen_range(0..entry_ids.len())].clone()));
+            let pick = self.rng.borrow_mut().gen_range(0..entry_ids.len());
+            return Ok(Some(entry_ids[pick].clone()));
         }
 
         let total_ids = candidate.total_emptypatch_ids.len() +
@@ -246,23 +381,149 @@ impl EvolutionStrategy {
                         candidate.total_unresolved_ids.len();
 
         // Solve empty patches
-        if candidate.total_emptypatch_ids.len() >= (total_ids as f64 * 0.1) as usize && rng.gen::<f64>() < 0.25 {
+        if candidate.total_emptypatch_ids.len() >= (total_ids as f64 * 0.1) as usize
+            && self.rng.borrow_mut().gen::<f64>() < 0.25
+        {
             return Ok(Some("solve_empty_patches".to_string()));
         }
 
         // Solve stochasticity
-        if rng.gen::<f64>() < 0.25 {
+        if self.rng.borrow_mut().gen::<f64>() < 0.25 {
             return Ok(Some("solve_stochasticity".to_string()));
         }
 
-        // Choose a random unresolved entry
+        // Choose a random unresolved entry, skipping ones whose entire ancestor chain already
+        // failed on it too (repeatedly reassigning a hereditarily-stuck entry wastes a rollout).
+        // Fall back to the unfiltered list if every candidate entry is hereditarily stuck.
         if candidate.total_unresolved_ids.is_empty() {
             return Ok(None);
         }
 
-        let entry = &candidate.total_unresolved_ids[rng.gen_range(0..candidate.total_unresolved_ids.len())];
+        let fresh_ids: Vec<&String> = candidate
+            .total_unresolved_ids
+            .iter()
+            .filter(|entry_id| !self.ancestor_chain_failed_entry(lineage, candidates, commit, entry_id))
+            .collect();
+
+        let pool = if fresh_ids.is_empty() {
+            candidate.total_unresolved_ids.iter().collect::<Vec<_>>()
+        } else {
+            fresh_ids
+        };
+
+        let pick = self.rng.borrow_mut().gen_range(0..pool.len());
+        let entry = pool[pick];
         Ok(Some(entry.clone()))
     }
+
+    /// Whether `commit` and every one of its ancestors already failed `entry_id` (as unresolved or
+    /// empty-patch), meaning this lineage has never produced a successful patch for it and
+    /// reassigning it again is unlikely to either.
+    fn ancestor_chain_failed_entry(
+        &self,
+        lineage: &LineageIndex,
+        candidates: &HashMap<String, CandidateInfo>,
+        commit: &str,
+        entry_id: &str,
+    ) -> bool {
+        let failed_at = |c: &str| {
+            candidates
+                .get(c)
+                .map(|info| {
+                    info.total_unresolved_ids.iter().any(|id| id == entry_id)
+                        || info.total_emptypatch_ids.iter().any(|id| id == entry_id)
+                })
+                .unwrap_or(false)
+        };
+
+        if !failed_at(commit) {
+            return false;
+        }
+
+        lineage.ancestors(commit).iter().all(|ancestor| failed_at(ancestor))
+    }
+
+    /// Walk `commit`'s ancestor chain back to `"initial"`, computing the accuracy delta at each
+    /// parent-to-child step, and pinpoint the single step with the largest drop along with which
+    /// task ids flipped from resolved to unresolved/empty-patch at that step. Mirrors a
+    /// bisect-perf-regressions walk, adapted to the DGM's commit-to-commit accuracy metric instead
+    /// of microbenchmark timings. Intended to be invoked after a generation completes, on any
+    /// candidate whose `accuracy_score` looks worse than an ancestor's.
+    pub fn diagnose_regression(
+        &self,
+        archive: &Archive,
+        output_dir: &Path,
+        commit: &str,
+    ) -> DgmResult<RegressionReport> {
+        let candidates = self.get_candidates(archive, output_dir)?;
+        let lineage = LineageIndex::build(archive, output_dir)?;
+
+        let mut chain = lineage.ancestors(commit);
+        chain.reverse();
+        chain.push(commit.to_string());
+
+        let mut steps = Vec::new();
+        for pair in chain.windows(2) {
+            let (parent_commit, child_commit) = (&pair[0], &pair[1]);
+            let (Some(parent), Some(child)) = (candidates.get(parent_commit), candidates.get(child_commit)) else {
+                continue;
+            };
+
+            let regressed_task_ids: Vec<String> = parent
+                .total_resolved_ids
+                .iter()
+                .filter(|id| {
+                    child.total_unresolved_ids.contains(id) || child.total_emptypatch_ids.contains(id)
+                })
+                .cloned()
+                .collect();
+
+            steps.push(RegressionStep {
+                parent_commit: parent_commit.clone(),
+                child_commit: child_commit.clone(),
+                parent_accuracy: parent.accuracy_score,
+                child_accuracy: child.accuracy_score,
+                delta: child.accuracy_score - parent.accuracy_score,
+                regressed_task_ids,
+            });
+        }
+
+        let worst_step = steps
+            .iter()
+            .min_by(|a, b| a.delta.partial_cmp(&b.delta).unwrap_or(std::cmp::Ordering::Equal))
+            .filter(|step| step.delta < 0.0)
+            .cloned();
+
+        Ok(RegressionReport {
+            commit: commit.to_string(),
+            steps,
+            worst_step,
+        })
+    }
+}
+
+/// One parent-to-child step in a [`RegressionReport`]'s lineage walk.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionStep {
+    pub parent_commit: String,
+    pub child_commit: String,
+    pub parent_accuracy: f64,
+    pub child_accuracy: f64,
+    /// `child_accuracy - parent_accuracy`; negative means performance regressed at this step.
+    pub delta: f64,
+    /// Task ids that were in `parent`'s `resolved_ids` but flipped to `unresolved_ids`/
+    /// `empty_patch_ids` in `child`.
+    pub regressed_task_ids: Vec<String>,
+}
+
+/// Result of [`EvolutionStrategy::diagnose_regression`]: every accuracy-delta step along
+/// `commit`'s ancestor chain back to `"initial"`, plus the single worst (most negative) one, if
+/// any step actually regressed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionReport {
+    pub commit: String,
+    pub steps: Vec<RegressionStep>,
+    pub worst_step: Option<RegressionStep>,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,152 @@
+use crate::{
+    dgm::Archive,
+    utils::{load_json_file, EvaluationResult},
+    DgmResult,
+};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use tracing::debug;
+
+/// A single-pass index over an [`Archive`]'s commit-lineage DAG: per commit, its parent, its
+/// direct children, and a generation number (distance from `"initial"`). Built once from the
+/// archive's `metadata.json` files instead of the repeated per-call file scans
+/// `EvolutionStrategy::get_candidates` otherwise does, mirroring the DAG index jujutsu's
+/// `lib/src/index.rs` builds over a repo's commit graph.
+pub struct LineageIndex {
+    commits: Vec<String>,
+    parent: HashMap<String, String>,
+    children: HashMap<String, Vec<String>>,
+    generation: HashMap<String, u32>,
+}
+
+impl LineageIndex {
+    /// Ingest every commit in `archive`, reading each one's `parent_commit` out of its
+    /// `metadata.json` under `output_dir` (skipping `"initial"`, which has none), then assigning
+    /// every commit's generation number in a single topological (BFS) pass out from the roots.
+    /// A commit with unreadable metadata, or whose recorded parent isn't itself in the archive, is
+    /// treated as its own root rather than failing the whole build.
+    pub fn build(archive: &Archive, output_dir: &Path) -> DgmResult<Self> {
+        let commits: Vec<String> = archive.get_commits().to_vec();
+        let mut parent: HashMap<String, String> = HashMap::new();
+        let mut children: HashMap<String, Vec<String>> = HashMap::new();
+
+        for commit in &commits {
+            children.entry(commit.clone()).or_default();
+        }
+
+        for commit in &commits {
+            if commit == "initial" {
+                continue;
+            }
+
+            let metadata_path = output_dir.join(commit).join("metadata.json");
+            match load_json_file::<EvaluationResult, _>(&metadata_path) {
+                Ok(metadata) if !metadata.parent_commit.is_empty() && commits.contains(&metadata.parent_commit) => {
+                    children.entry(metadata.parent_commit.clone()).or_default().push(commit.clone());
+                    parent.insert(commit.clone(), metadata.parent_commit);
+                }
+                Ok(_) => {
+                    debug!("Commit {} has no archived parent recorded; treating it as a lineage root", commit);
+                }
+                Err(e) => {
+                    debug!("Commit {} has no readable metadata for lineage indexing: {}", commit, e);
+                }
+            }
+        }
+
+        let mut generation: HashMap<String, u32> = HashMap::new();
+        let mut queue: VecDeque<String> = VecDeque::new();
+
+        for commit in &commits {
+            if !parent.contains_key(commit) {
+                generation.insert(commit.clone(), 0);
+                queue.push_back(commit.clone());
+            }
+        }
+
+        while let Some(commit) = queue.pop_front() {
+            let next_generation = generation[&commit] + 1;
+            for child in children.get(&commit).cloned().unwrap_or_default() {
+                if !generation.contains_key(&child) {
+                    generation.insert(child.clone(), next_generation);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        Ok(Self { commits, parent, children, generation })
+    }
+
+    /// `commit`'s distance from `"initial"`. `None` if `commit` wasn't part of the archive this
+    /// index was built from, or if no lineage root reaches it (a parentage cycle in corrupt
+    /// metadata, see [`Self::ancestors`]).
+    pub fn generation(&self, commit: &str) -> Option<u32> {
+        self.generation.get(commit).copied()
+    }
+
+    /// `commit`'s recorded parent, if any (e.g. `"initial"` and any commit whose metadata
+    /// couldn't be resolved to an in-archive parent have none).
+    pub fn parent(&self, commit: &str) -> Option<&str> {
+        self.parent.get(commit).map(String::as_str)
+    }
+
+    /// `commit`'s direct children, in the order they were encountered while building the index.
+    pub fn children(&self, commit: &str) -> &[String] {
+        self.children.get(commit).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Every ancestor of `commit`, nearest first, not including `commit` itself. Guards against
+    /// corrupt/hand-edited archive metadata encoding a parentage cycle (e.g. A's parent is B and
+    /// B's parent is A — `build()` only roots commits that have *no* recorded parent, so a cycle
+    /// like this passes through it undetected) by stopping as soon as a commit is revisited,
+    /// rather than walking `parent` forever.
+    pub fn ancestors(&self, commit: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(commit.to_string());
+        let mut current = commit;
+        while let Some(parent) = self.parent(current) {
+            if !seen.insert(parent.to_string()) {
+                break;
+            }
+            result.push(parent.to_string());
+            current = result.last().unwrap();
+        }
+        result
+    }
+
+    /// Every descendant of `commit`, transitively, not including `commit` itself.
+    pub fn descendants(&self, commit: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut queue: VecDeque<String> = self.children(commit).to_vec().into();
+
+        while let Some(next) = queue.pop_front() {
+            if result.contains(&next) {
+                continue;
+            }
+            queue.extend(self.children(&next).iter().cloned());
+            result.push(next);
+        }
+
+        result
+    }
+
+    /// Whether `a` is an ancestor of `b` (i.e. `b` descends from `a`).
+    pub fn is_ancestor(&self, a: &str, b: &str) -> bool {
+        self.ancestors(b).iter().any(|ancestor| ancestor == a)
+    }
+
+    /// The shortest prefix of `commit` that's unambiguous across every commit in the index, for
+    /// compact CLI/log display. Falls back to the full `commit` if no prefix shorter than it is
+    /// unique (e.g. `commit` is itself a prefix of another archived commit).
+    pub fn shortest_prefix(&self, commit: &str) -> String {
+        let chars: Vec<char> = commit.chars().collect();
+        for len in 1..chars.len() {
+            let prefix: String = chars[..len].iter().collect();
+            if self.commits.iter().filter(|candidate| candidate.starts_with(&prefix)).count() <= 1 {
+                return prefix;
+            }
+        }
+        commit.to_string()
+    }
+}
@@ -1,9 +1,11 @@
 pub mod archive;
 pub mod evolution;
+pub mod lineage;
 pub mod runner;
 
 pub use archive::*;
 pub use evolution::*;
+pub use lineage::*;
 pub use runner::*;
 
 use crate::{DgmResult, Deserialize, Serialize};
@@ -39,6 +41,9 @@ pub struct DgmConfig {
     pub eval_noise: f64,
     pub no_full_eval: bool,
     pub run_baseline: Option<String>,
+    /// Seed for the `EvolutionStrategy`'s parent/entry selection. `None` draws a seed from
+    /// entropy and logs it, so the run can still be reproduced later from the log.
+    pub selection_seed: Option<u64>,
 }
 
 impl DgmConfig {
@@ -57,6 +62,7 @@ impl DgmConfig {
         eval_noise: f64,
         no_full_eval: bool,
         run_baseline: Option<String>,
+        selection_seed: Option<u64>,
     ) -> Self {
         Self {
             max_generation,
@@ -72,6 +78,7 @@ impl DgmConfig {
             eval_noise,
             no_full_eval,
             run_baseline,
+            selection_seed,
         }
     }
 
@@ -88,7 +95,7 @@ impl DgmConfig {
             return Err(anyhow::anyhow!("selfimprove_workers must be greater than 0"));
         }
 
-        let valid_methods = ["random", "score_prop", "score_child_prop", "best"];
+        let valid_methods = ["random", "score_prop", "score_child_prop", "best", "wpm", "novelty_depth"];
         if !valid_methods.contains(&self.choose_selfimproves_method.as_str()) {
             return Err(anyhow::anyhow!(
                 "Invalid choose_selfimproves_method: {}. Must be one of: {:?}",
@@ -1,11 +1,11 @@
 use crate::{
     config::DgmConfig as Config,
-    dgm::{Archive, DgmConfig, EvolutionStrategy},
-    utils::{ensure_dir_exists, generate_run_id, load_json_file},
+    dgm::{Archive, DgmConfig, EvolutionStrategy, SelfImproveEntry},
+    utils::{ensure_dir_exists, generate_run_id, load_json_file, EvaluationResult, ImprovementDiagnosis, RepoPool},
     DgmResult,
 };
 use std::path::{Path, PathBuf};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 #[derive(Debug)]
 pub struct DgmRunner {
@@ -13,6 +13,9 @@ pub struct DgmRunner {
     api_config: Config,
     output_dir: PathBuf,
     run_id: String,
+    /// Caches opened `GitManager` handles and resolved commit diffs across generations, so the
+    /// evolution loop isn't re-opening the same repo and re-reading the same commits every pass.
+    repo_pool: RepoPool,
 }
 
 impl DgmRunner {
@@ -31,6 +34,7 @@ impl DgmRunner {
         eval_noise: f64,
         no_full_eval: bool,
         run_baseline: Option<String>,
+        selection_seed: Option<u64>,
     ) -> DgmResult<Self> {
         let config = DgmConfig::new(
             max_generation,
@@ -46,6 +50,7 @@ impl DgmRunner {
             eval_noise,
             no_full_eval,
             run_baseline,
+            selection_seed,
         );
 
         config.validate()?;
@@ -70,6 +75,7 @@ impl DgmRunner {
             api_config,
             output_dir,
             run_id,
+            repo_pool: RepoPool::default(),
         })
     }
 
@@ -89,7 +95,10 @@ impl DgmRunner {
         info!("Starting evolution from generation {}", start_gen_num);
         info!("Archive: {:?}", archive.get_commits());
 
-        let evolution_strategy = EvolutionStrategy::new(self.config.choose_selfimproves_method.clone());
+        let evolution_strategy = EvolutionStrategy::new(
+            self.config.choose_selfimproves_method.clone(),
+            self.config.selection_seed,
+        );
 
         // Run the DGM evolution loop
         for gen_num in start_gen_num..self.config.max_generation {
@@ -117,6 +126,16 @@ impl DgmRunner {
                 .map(|_| generate_run_id())
                 .collect();
 
+            // Give each candidate its own branch off its parent commit, so concurrent
+            // `selfimprove_workers` can mutate the same repo clone in parallel instead of
+            // serializing through a single checked-out working tree via reset + clean.
+            self.branch_selfimprove_candidates(&selfimprove_ids, &selfimprove_entries);
+
+            // Record quantitative change-size signals (files/insertions/deletions) for each
+            // candidate, when requested, so later generations can bias toward small, high-yield
+            // edits instead of reading an opaque improvement_diagnosis blob.
+            self.diagnose_selfimprove_candidates(&selfimprove_ids, &selfimprove_entries);
+
             let selfimprove_ids_compiled = selfimprove_ids.clone();
 
             // Update archive
@@ -210,6 +229,84 @@ impl DgmRunner {
         Ok(())
     }
 
+    /// Create one branch per self-improvement candidate, named after its generated id and
+    /// rooted at its `parent_commit`, on the repo clone under `<output_dir>/initial`. A worker
+    /// can then check out its own candidate's branch, apply/commit its patch, and evaluate
+    /// entirely within that branch without stepping on another worker's in-progress changes -
+    /// the repo clone itself can stay shared across `selfimprove_workers`. Best-effort: a
+    /// missing/non-git `initial` directory just skips isolation rather than failing the
+    /// generation, since the candidates themselves still evaluate against the copied files.
+    fn branch_selfimprove_candidates(&self, candidate_ids: &[String], entries: &[SelfImproveEntry]) {
+        let repo_dir = self.output_dir.join("initial");
+
+        let git_manager = match self.repo_pool.get_repo(&repo_dir) {
+            Ok(git_manager) => git_manager,
+            Err(e) => {
+                warn!("No git repository at {:?} ({}); skipping branch isolation", repo_dir, e);
+                return;
+            }
+        };
+
+        for (candidate_id, entry) in candidate_ids.iter().zip(entries.iter()) {
+            if let Err(e) = git_manager.create_branch(candidate_id, &entry.parent_commit) {
+                warn!(
+                    "Failed to create isolation branch {} off {}: {}",
+                    candidate_id, entry.parent_commit, e
+                );
+            }
+        }
+    }
+
+    /// Compute and persist an [`ImprovementDiagnosis`] for each self-improvement candidate, when
+    /// `post_improve_diagnose` is enabled. Best-effort, like `branch_selfimprove_candidates`: a
+    /// candidate without a `metadata.json` yet just has its diagnosis skipped rather than
+    /// failing the generation.
+    fn diagnose_selfimprove_candidates(&self, candidate_ids: &[String], entries: &[SelfImproveEntry]) {
+        if !self.config.post_improve_diagnose {
+            return;
+        }
+
+        let repo_dir = self.output_dir.join("initial");
+        let git_manager = match self.repo_pool.get_repo(&repo_dir) {
+            Ok(git_manager) => git_manager,
+            Err(e) => {
+                warn!("No git repository at {:?} ({}); skipping improvement diagnosis", repo_dir, e);
+                return;
+            }
+        };
+
+        for (candidate_id, entry) in candidate_ids.iter().zip(entries.iter()) {
+            let stats = match git_manager.diff_stats_versus_commit(&entry.parent_commit) {
+                Ok(stats) => stats,
+                Err(e) => {
+                    warn!("Failed to compute diff stats for candidate {}: {}", candidate_id, e);
+                    continue;
+                }
+            };
+
+            let metadata_path = self.output_dir.join(candidate_id).join("metadata.json");
+            let mut metadata: EvaluationResult = match load_json_file(&metadata_path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    debug!("No metadata.json yet for candidate {} ({}); skipping diagnosis", candidate_id, e);
+                    continue;
+                }
+            };
+
+            let diagnosis = ImprovementDiagnosis::from_diff_stats(&stats);
+            metadata.improvement_diagnosis = serde_json::to_value(&diagnosis).ok();
+
+            match serde_json::to_string_pretty(&metadata) {
+                Ok(json) => {
+                    if let Err(e) = std::fs::write(&metadata_path, json) {
+                        warn!("Failed to write improvement diagnosis for candidate {}: {}", candidate_id, e);
+                    }
+                }
+                Err(e) => warn!("Failed to serialize metadata for candidate {}: {}", candidate_id, e),
+            }
+        }
+    }
+
     /// Get the API configuration for use in LLM client creation
     pub fn get_api_config(&self) -> &Config {
         &self.api_config
@@ -0,0 +1,223 @@
+use crate::agent::AgenticSystem;
+use crate::DgmResult;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::fs;
+use tracing::{info, warn};
+
+/// One instance to run through the agent, as a JSON document a maintainer can check into a
+/// fixed benchmark suite and re-run across DGM generations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkloadInstance {
+    pub instance_id: String,
+    pub problem_statement: String,
+    pub git_dir: PathBuf,
+    pub base_commit: String,
+    pub model: String,
+    pub test_description: Option<String>,
+    /// Substring [`BenchmarkRunner::run_instance`]'s regression-test run must contain for the
+    /// instance to score as accurate. `None` falls back to scoring on whether the agent produced
+    /// a non-empty diff at all.
+    pub expected_regression_tests_summary: Option<String>,
+}
+
+/// A workload file: a named, repeatable suite of [`WorkloadInstance`]s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workload {
+    pub name: String,
+    pub instances: Vec<WorkloadInstance>,
+}
+
+/// Recorded outcome for one [`WorkloadInstance`], as produced by
+/// [`BenchmarkRunner::run_instance`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkInstanceResult {
+    pub instance_id: String,
+    pub model: String,
+    pub duration_seconds: f64,
+    pub iterations: u32,
+    pub accuracy: f64,
+    pub diff_size_bytes: usize,
+    pub regression_tests_summary: String,
+    pub error: Option<String>,
+}
+
+/// Aggregated report for one [`Workload`] run, as POSTed to
+/// [`BenchmarkRunner`]'s `results_endpoint` (if configured) and returned to the caller, so a
+/// maintainer can diff it between generations of the DGM archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub workload_name: String,
+    pub instances: Vec<BenchmarkInstanceResult>,
+    pub total_duration_seconds: f64,
+    pub average_accuracy: f64,
+}
+
+impl BenchmarkReport {
+    fn new(workload_name: String, instances: Vec<BenchmarkInstanceResult>) -> Self {
+        let total_duration_seconds = instances.iter().map(|r| r.duration_seconds).sum();
+        let average_accuracy = if instances.is_empty() {
+            0.0
+        } else {
+            instances.iter().map(|r| r.accuracy).sum::<f64>() / instances.len() as f64
+        };
+
+        Self {
+            workload_name,
+            instances,
+            total_duration_seconds,
+            average_accuracy,
+        }
+    }
+}
+
+/// Load a [`Workload`] from a JSON file.
+pub async fn load_workload(path: &Path) -> DgmResult<Workload> {
+    let content = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read workload file: {:?}", path))?;
+
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse workload JSON: {:?}", path))
+}
+
+/// Runs [`Workload`] files through [`AgenticSystem::forward`] and `run_regression_tests`,
+/// recording per-instance timing, iteration count, accuracy, and diff size so maintainers can
+/// track agent/archive performance over a fixed suite across generations instead of running
+/// single instances ad hoc.
+pub struct BenchmarkRunner {
+    /// HTTP endpoint each [`BenchmarkReport`] is POSTed to as JSON after a workload completes.
+    /// `None` skips reporting; the report is always returned to the caller regardless.
+    results_endpoint: Option<String>,
+    http_client: reqwest::Client,
+}
+
+impl BenchmarkRunner {
+    pub fn new(results_endpoint: Option<String>) -> Self {
+        Self {
+            results_endpoint,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Run every workload file in `paths` in turn, returning one [`BenchmarkReport`] per file in
+    /// the same order.
+    pub async fn run_workload_files(&self, paths: &[PathBuf], outdir: &Path) -> DgmResult<Vec<BenchmarkReport>> {
+        let mut reports = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let workload = load_workload(path).await?;
+            info!(
+                "Running benchmark workload '{}' ({} instances) from {:?}",
+                workload.name,
+                workload.instances.len(),
+                path
+            );
+            reports.push(self.run_workload(&workload, outdir).await?);
+        }
+
+        Ok(reports)
+    }
+
+    /// Run every instance in `workload` sequentially (each instance owns its own git checkout
+    /// via `git_dir`, so there's no shared mutable state to race on), report on the result, and
+    /// optionally POST the aggregated report to `results_endpoint`.
+    pub async fn run_workload(&self, workload: &Workload, outdir: &Path) -> DgmResult<BenchmarkReport> {
+        let mut instance_results = Vec::with_capacity(workload.instances.len());
+
+        for instance in &workload.instances {
+            let result = self.run_instance(instance, outdir).await.unwrap_or_else(|e| {
+                warn!("Benchmark instance '{}' failed: {}", instance.instance_id, e);
+                BenchmarkInstanceResult {
+                    instance_id: instance.instance_id.clone(),
+                    model: instance.model.clone(),
+                    duration_seconds: 0.0,
+                    iterations: 0,
+                    accuracy: 0.0,
+                    diff_size_bytes: 0,
+                    regression_tests_summary: String::new(),
+                    error: Some(e.to_string()),
+                }
+            });
+            instance_results.push(result);
+        }
+
+        let report = BenchmarkReport::new(workload.name.clone(), instance_results);
+
+        if let Some(endpoint) = &self.results_endpoint {
+            if let Err(e) = self.post_report(endpoint, &report).await {
+                warn!("Failed to post benchmark report to {}: {}", endpoint, e);
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn run_instance(&self, instance: &WorkloadInstance, outdir: &Path) -> DgmResult<BenchmarkInstanceResult> {
+        let start = Instant::now();
+        let chat_history_file = outdir.join(format!("{}_chat_history.md", instance.instance_id));
+
+        let agentic_system = AgenticSystem::new(
+            instance.problem_statement.clone(),
+            instance.git_dir.clone(),
+            instance.base_commit.clone(),
+            chat_history_file,
+            instance.test_description.clone(),
+            false,
+            Some(instance.instance_id.clone()),
+            &instance.model,
+        )
+        .await
+        .with_context(|| format!("Failed to set up instance '{}'", instance.instance_id))?;
+
+        let iterations = agentic_system.forward().await?;
+        let diff = agentic_system.get_current_edits().await?;
+        let regression_tests = agentic_system.get_regression_tests().await?;
+        let regression_tests_summary = agentic_system.run_regression_tests(&regression_tests).await?;
+
+        let accuracy = match &instance.expected_regression_tests_summary {
+            Some(expected) => {
+                if regression_tests_summary.contains(expected.as_str()) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            None => {
+                if diff.trim().is_empty() {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        };
+
+        Ok(BenchmarkInstanceResult {
+            instance_id: instance.instance_id.clone(),
+            model: instance.model.clone(),
+            duration_seconds: start.elapsed().as_secs_f64(),
+            iterations,
+            accuracy,
+            diff_size_bytes: diff.len(),
+            regression_tests_summary,
+            error: None,
+        })
+    }
+
+    async fn post_report(&self, endpoint: &str, report: &BenchmarkReport) -> DgmResult<()> {
+        let response = self
+            .http_client
+            .post(endpoint)
+            .json(report)
+            .send()
+            .await
+            .with_context(|| format!("Failed to POST benchmark report to {}", endpoint))?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Benchmark results endpoint {} returned {}", endpoint, response.status());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use super::EvaluationResult;
+use crate::DgmResult;
+use anyhow::Context;
+use std::path::Path;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// Append-only JSONL checkpoint of completed [`EvaluationResult`]s, one per line, so a crashed or
+/// cancelled evaluation run can resume without re-running containers that already finished.
+///
+/// This is distinct from the per-instance `<instance_id>.json` files `process_*_entry` already
+/// writes: those let a single retried entry short-circuit its own container run, while this is
+/// one file a caller reads up front via [`Self::load`] to know the full set of already-done
+/// `instance_id`s before submitting anything to the scheduler at all.
+pub struct Checkpoint {
+    file: fs::File,
+}
+
+impl Checkpoint {
+    /// Open (creating if necessary) the checkpoint file at `path` for appending.
+    pub async fn open(path: &Path) -> DgmResult<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("Failed to open checkpoint file {:?}", path))?;
+
+        Ok(Self { file })
+    }
+
+    /// Append `result` to the checkpoint as one JSON line.
+    pub async fn append(&mut self, result: &EvaluationResult) -> DgmResult<()> {
+        let mut line =
+            serde_json::to_string(result).context("Failed to serialize result for checkpoint")?;
+        line.push('\n');
+
+        self.file
+            .write_all(line.as_bytes())
+            .await
+            .context("Failed to append to checkpoint file")?;
+
+        Ok(())
+    }
+
+    /// Read back every result already recorded at `path`. Returns an empty vec if the file
+    /// doesn't exist yet (a fresh run). A malformed trailing line - e.g. a checkpoint write that
+    /// was cut off mid-append by a crash - is skipped rather than failing the whole load.
+    pub async fn load(path: &Path) -> DgmResult<Vec<EvaluationResult>> {
+        let content = match fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read checkpoint file {:?}", path))
+            }
+        };
+
+        let mut results = Vec::new();
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<EvaluationResult>(line) {
+                Ok(result) => results.push(result),
+                Err(e) => warn!("Skipping malformed checkpoint line in {:?}: {}", path, e),
+            }
+        }
+
+        Ok(results)
+    }
+}
@@ -1,15 +1,55 @@
+pub mod bench;
+mod checkpoint;
+mod progress;
+
 use crate::config::DgmConfig;
-use crate::utils::docker::DockerManager;
+use crate::utils::batch::BatchRunner;
+use crate::utils::docker::{DockerEndpoint, DockerManager, LogChannel};
+use crate::utils::docker_scheduler::{DockerEndpointConfig, DockerScheduler};
 use crate::DgmResult;
 use anyhow::Context;
+use checkpoint::Checkpoint;
 use futures::future::join_all;
+use progress::EvaluationMetrics;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
-use tokio::sync::Semaphore;
-use tracing::{error, info, warn};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn, Instrument};
+
+/// Stage of a container run, recorded on [`EvaluationResult::failed_stage`] when that stage is
+/// the one that errored, so a partial result (e.g. a missing chat history because the agent
+/// crashed mid-run) stays diagnosable instead of just showing a generic error string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStage {
+    /// Create and start the container, and seed it with the DGM framework files.
+    Prepare,
+    /// Apply model patches and install requirements.
+    Setup,
+    /// Run the coding agent.
+    Execute,
+    /// Gather `model_patch.diff`, proposed patches, chat history, and (Polyglot) test results.
+    Collect,
+    /// Stop and remove the container.
+    Teardown,
+}
+
+impl std::fmt::Display for RunStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            RunStage::Prepare => "prepare",
+            RunStage::Setup => "setup",
+            RunStage::Execute => "execute",
+            RunStage::Collect => "collect",
+            RunStage::Teardown => "teardown",
+        };
+        write!(f, "{}", s)
+    }
+}
 
 /// Evaluation result for a single instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +61,14 @@ pub struct EvaluationResult {
     pub eval_result: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Which stage of the container run failed, if any. `None` on a clean run even if
+    /// `eval_result` is something other than "resolved" (e.g. "empty_patch" is not a failure).
+    pub failed_stage: Option<RunStage>,
+    /// Per-test pass/fail outcomes parsed from the test framework's output (test name -> passed),
+    /// populated by [`EvaluationHarness::run_language_evaluation`] for languages with a result
+    /// parser (python, rust, java), whichever way the instance was scored. `None` for other
+    /// languages, or when the test suite never ran.
+    pub test_results: Option<HashMap<String, bool>>,
 }
 
 /// SWE-bench dataset entry
@@ -45,6 +93,21 @@ pub struct PolyglotEntry {
     pub test_commit: String,
     pub language: String,
     pub files: PolyglotFiles,
+    /// Optional per-fd output assertions (fd number, e.g. `1` for stdout or `2` for stderr, ->
+    /// a regex the captured output on that fd must match). When `fail_to_pass`/`pass_to_pass`
+    /// are empty, `run_language_evaluation` only marks an instance `"resolved"` when the exit
+    /// code is zero AND every listed fd matches; a missing or empty map preserves the previous
+    /// pure exit-code behavior. When either test list is set, this map is not checked.
+    pub expected_output: Option<HashMap<u8, String>>,
+    /// Tests that must go from failing to passing for this instance to be `"resolved"`. Named by
+    /// the test framework's own identifier (e.g. pytest node id, cargo test path, or
+    /// `classname.name` for JUnit).
+    #[serde(default)]
+    pub fail_to_pass: Vec<String>,
+    /// Tests that must stay passing for this instance to be `"resolved"`, alongside every
+    /// `fail_to_pass` test.
+    #[serde(default)]
+    pub pass_to_pass: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,20 +118,104 @@ pub struct PolyglotFiles {
 /// Evaluation harness for running benchmarks
 pub struct EvaluationHarness {
     config: DgmConfig,
-    docker_manager: Arc<DockerManager>,
-    max_workers: usize,
+    scheduler: Arc<DockerScheduler>,
+    metrics: Arc<EvaluationMetrics>,
+}
+
+/// Outcome of a staged SWE-bench container run. `model_patch`/`proposed_patches` are populated
+/// by the Collect stage whenever the container was created, even if an earlier stage failed, so
+/// a crash partway through never silently drops a partial patch. `error`/`failed_stage` are
+/// `None` on a clean run.
+struct SweBenchRunOutcome {
+    model_patch: String,
+    proposed_patches: Vec<String>,
+    failed_stage: Option<RunStage>,
+    error: Option<anyhow::Error>,
+}
+
+/// Outcome of a staged Polyglot container run, mirroring [`SweBenchRunOutcome`]. `eval_result`
+/// is "error" whenever `error` is `Some`, and otherwise one of "empty_patch"/"resolved"/"unresolved".
+struct PolyglotRunOutcome {
+    model_patch: String,
+    eval_result: String,
+    /// Per-test pass/fail outcomes, when the language has a result parser and `run_language_evaluation`
+    /// got far enough to run the test suite. Carried through to [`EvaluationResult::test_results`].
+    test_results: Option<HashMap<String, bool>>,
+    failed_stage: Option<RunStage>,
+    error: Option<anyhow::Error>,
 }
 
 impl EvaluationHarness {
-    /// Create a new evaluation harness
-    pub fn new(config: DgmConfig, max_workers: usize) -> DgmResult<Self> {
-        let docker_manager = Arc::new(DockerManager::new()?);
+    /// Create a new evaluation harness, connecting to every Docker endpoint configured in
+    /// `config.docker.endpoints` up front so a large evaluation run can fan out across a build
+    /// farm instead of pinning every container to one daemon.
+    pub fn new(config: DgmConfig) -> DgmResult<Self> {
+        let endpoint_configs = config
+            .docker
+            .endpoints
+            .iter()
+            .map(|spec| DockerEndpointConfig {
+                name: spec.name.clone(),
+                endpoint: match &spec.address {
+                    Some(address) => DockerEndpoint::Tcp { address: address.clone() },
+                    None => DockerEndpoint::Local,
+                },
+                num_max_jobs: spec.num_max_jobs as usize,
+                min_api_version: spec.min_api_version.clone(),
+            })
+            .collect();
 
-        Ok(Self {
-            config,
-            docker_manager,
-            max_workers,
-        })
+        let scheduler = Arc::new(DockerScheduler::new(endpoint_configs)?);
+        let metrics = EvaluationMetrics::new();
+
+        if let Some(bind_address) = config.evaluation.admin_bind_address.clone() {
+            let metrics = metrics.clone();
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                if let Err(e) = progress::serve(bind_address, metrics, scheduler).await {
+                    error!("Evaluation progress server exited: {}", e);
+                }
+            });
+        }
+
+        Ok(Self { config, scheduler, metrics })
+    }
+
+    /// Worker count for [`BatchRunner`], from `config.evaluation.max_workers`. `0` means
+    /// "unset" and defers to `BatchRunner::new`'s available-CPUs default.
+    fn worker_count(&self) -> Option<usize> {
+        if self.config.evaluation.max_workers == 0 {
+            None
+        } else {
+            Some(self.config.evaluation.max_workers as usize)
+        }
+    }
+
+    /// Make sure every image in `image_names` exists on every Docker endpoint the scheduler can
+    /// dispatch to, pulling/building each once up front rather than discovering a missing image
+    /// lazily inside a container create hundreds of entries into a run. Each endpoint's
+    /// `DockerManager` serializes its own pulls via `ensure_image`, so concurrent entries racing
+    /// on the same image never trigger duplicate pulls against one daemon.
+    async fn ensure_images_available(
+        scheduler: &DockerScheduler,
+        image_names: impl Iterator<Item = String>,
+    ) -> DgmResult<()> {
+        let image_names: std::collections::HashSet<String> = image_names.collect();
+        let docker_managers = scheduler.docker_managers();
+
+        let checks = docker_managers.iter().flat_map(|docker_manager| {
+            image_names.iter().map(move |image_name| {
+                let docker_manager = docker_manager.clone();
+                let image_name = image_name.clone();
+                async move { docker_manager.ensure_image(&image_name).await }
+            })
+        });
+
+        for result in join_all(checks).await {
+            result?;
+        }
+
+        Ok(())
     }
 
     /// Run SWE-bench evaluation
@@ -85,60 +232,79 @@ impl EvaluationHarness {
         fs::create_dir_all(output_dir).await
             .context("Failed to create output directory")?;
 
-        // Create semaphore to limit concurrent workers
-        let semaphore = Arc::new(Semaphore::new(self.max_workers));
-
-        // Process entries in parallel
-        let tasks: Vec<_> = entries
-            .into_iter()
-            .map(|entry| {
-                let semaphore = semaphore.clone();
-                let docker_manager = self.docker_manager.clone();
-                let config = self.config.clone();
-                let model_name = model_name_or_path.to_string();
+        // Resume support: instances already recorded in the checkpoint are dropped from the
+        // work list entirely instead of re-entering the scheduler, so a crashed or cancelled run
+        // picks up without re-running any container that already finished.
+        let checkpoint_path = output_dir.join("checkpoint.jsonl");
+        let checkpointed_results = Checkpoint::load(&checkpoint_path).await?;
+        let already_done: HashSet<String> =
+            checkpointed_results.iter().map(|r| r.instance_id.clone()).collect();
+        let entries: Vec<SWEBenchEntry> =
+            entries.into_iter().filter(|e| !already_done.contains(&e.instance_id)).collect();
+        if !already_done.is_empty() {
+            info!(
+                "Resuming from checkpoint: {} instances already completed, {} remaining",
+                already_done.len(),
+                entries.len()
+            );
+        }
+        let checkpoint = Arc::new(Mutex::new(Checkpoint::open(&checkpoint_path).await?));
+
+        // Resolve the distinct set of images this run will need and make sure they all exist
+        // before submitting any entry, instead of failing on a missing image deep into the run.
+        let image_names = entries
+            .iter()
+            .map(|entry| format!("swe_bench_{}", entry.repo.replace("/", "_")));
+        Self::ensure_images_available(&self.scheduler, image_names).await?;
+
+        self.metrics.set_total(already_done.len() + entries.len());
+
+        // Run entries in batches sized off the configured worker count (available CPUs if
+        // unset), bounded by a semaphore so at most that many containers are ever in flight at
+        // once - a large sweep stays within memory/handle limits instead of spawning every
+        // entry's container up front. Each task still submits through the scheduler so it lands
+        // on whichever configured Docker endpoint is currently least loaded.
+        let batch_runner = BatchRunner::new(self.worker_count());
+        let scheduler = self.scheduler.clone();
+        let config = self.config.clone();
+        let metrics = self.metrics.clone();
+        let model_name = model_name_or_path.to_string();
+        let output_path = output_dir.to_path_buf();
+
+        let new_results = batch_runner
+            .run(entries, move |entry| {
+                let scheduler = scheduler.clone();
+                let config = config.clone();
+                let metrics = metrics.clone();
+                let model_name = model_name.clone();
                 let model_patches = model_patch_paths.clone();
-                let output_path = output_dir.to_path_buf();
-
-                tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.unwrap();
-                    Self::process_swe_bench_entry(
-                        entry,
-                        &output_path,
-                        &model_name,
-                        model_patches,
-                        docker_manager,
-                        config,
-                    ).await
-                })
+                let output_path = output_path.clone();
+                let checkpoint = checkpoint.clone();
+
+                async move {
+                    scheduler
+                        .submit(|docker_manager| async move {
+                            Self::process_swe_bench_entry(
+                                entry,
+                                &output_path,
+                                &model_name,
+                                model_patches,
+                                docker_manager,
+                                config,
+                                metrics,
+                                checkpoint,
+                            ).await
+                        })
+                        .await
+                }
             })
-            .collect();
-
-        // Wait for all tasks to complete
-        let results = join_all(tasks).await;
+            .await;
 
-        // Collect results
-        let mut evaluation_results = Vec::new();
-        for result in results {
-            match result {
-                Ok(eval_result) => evaluation_results.push(eval_result),
-                Err(e) => {
-                    error!("Task failed: {}", e);
-                    // Create error result
-                    evaluation_results.push(EvaluationResult {
-                        instance_id: "unknown".to_string(),
-                        model_name_or_path: model_name_or_path.to_string(),
-                        model_patch: String::new(),
-                        proposed_model_patches: Vec::new(),
-                        eval_result: "error".to_string(),
-                        success: false,
-                        error: Some(e.to_string()),
-                    });
-                }
-            }
-        }
+        let mut results = checkpointed_results;
+        results.extend(new_results);
 
-        info!("SWE-bench evaluation completed with {} results", evaluation_results.len());
-        Ok(evaluation_results)
+        info!("SWE-bench evaluation completed with {} results", results.len());
+        Ok(results)
     }
 
     /// Process a single SWE-bench entry
@@ -149,6 +315,8 @@ impl EvaluationHarness {
         model_patch_paths: Option<Vec<PathBuf>>,
         docker_manager: Arc<DockerManager>,
         config: DgmConfig,
+        metrics: Arc<EvaluationMetrics>,
+        checkpoint: Arc<Mutex<Checkpoint>>,
     ) -> EvaluationResult {
         let instance_id = &entry.instance_id;
         info!("Processing SWE-bench entry: {}", instance_id);
@@ -159,11 +327,14 @@ impl EvaluationHarness {
             info!("Skipping existing entry: {}", instance_id);
             if let Ok(content) = fs::read_to_string(&result_file).await {
                 if let Ok(result) = serde_json::from_str::<EvaluationResult>(&content) {
+                    metrics.finish_instance(instance_id, result.success);
                     return result;
                 }
             }
         }
 
+        metrics.start_instance(instance_id, RunStage::Prepare.to_string().as_str());
+
         let mut result = EvaluationResult {
             instance_id: instance_id.clone(),
             model_name_or_path: model_name_or_path.to_string(),
@@ -172,34 +343,48 @@ impl EvaluationHarness {
             eval_result: "incomplete".to_string(),
             success: false,
             error: None,
+            failed_stage: None,
+            test_results: None,
         };
 
         // Create container name with timestamp
         let run_id = chrono::Utc::now().format("%Y%m%d_%H%M%S_%f").to_string();
         let container_name = format!("swe_bench_{}_{}", instance_id, run_id);
 
-        match Self::run_swe_bench_container(
+        let outcome = Self::run_swe_bench_container(
             &entry,
             &container_name,
             model_patch_paths,
             &docker_manager,
             &config,
             output_dir,
-        ).await {
-            Ok((model_patch, proposed_patches)) => {
-                result.model_patch = model_patch;
-                result.proposed_model_patches = proposed_patches;
+            &metrics,
+        ).await;
+
+        result.model_patch = outcome.model_patch;
+        result.proposed_model_patches = outcome.proposed_patches;
+        result.failed_stage = outcome.failed_stage;
+
+        match outcome.error {
+            None => {
                 result.eval_result = "completed".to_string();
                 result.success = true;
                 info!("Successfully processed SWE-bench entry: {}", instance_id);
             }
-            Err(e) => {
+            Some(e) => {
                 result.error = Some(e.to_string());
                 result.eval_result = "error".to_string();
-                error!("Failed to process SWE-bench entry {}: {}", instance_id, e);
+                error!(
+                    "Failed to process SWE-bench entry {} at stage {}: {}",
+                    instance_id,
+                    outcome.failed_stage.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+                    e
+                );
             }
         }
 
+        metrics.finish_instance(instance_id, result.success);
+
         // Save result to file
         if let Ok(json_content) = serde_json::to_string_pretty(&result) {
             if let Err(e) = fs::write(&result_file, json_content).await {
@@ -207,10 +392,16 @@ impl EvaluationHarness {
             }
         }
 
+        if let Err(e) = checkpoint.lock().await.append(&result).await {
+            warn!("Failed to append checkpoint for {}: {}", instance_id, e);
+        }
+
         result
     }
 
-    /// Run SWE-bench evaluation in a Docker container
+    /// Run SWE-bench evaluation in a Docker container, staged as Prepare -> Setup -> Execute ->
+    /// Collect -> Teardown. Collect and Teardown always run once the container exists, so an
+    /// early agent exit or a mid-run error still leaves whatever artifacts exist on disk.
     async fn run_swe_bench_container(
         entry: &SWEBenchEntry,
         container_name: &str,
@@ -218,38 +409,99 @@ impl EvaluationHarness {
         docker_manager: &DockerManager,
         _config: &DgmConfig,
         output_dir: &Path,
-    ) -> DgmResult<(String, Vec<String>)> {
-        // Build or get the appropriate Docker image for this entry
+        metrics: &EvaluationMetrics,
+    ) -> SweBenchRunOutcome {
         let image_name = format!("swe_bench_{}", entry.repo.replace("/", "_"));
 
-        // Create and start container
-        let container_id = docker_manager.create_container(
+        // Prepare: create the container. If this fails there is nothing to collect or tear down.
+        let container_id = match docker_manager.create_container(
             &image_name,
             container_name,
             Some("/dgm"),
             Some(Self::get_environment_variables()),
-        ).await?;
+        ).await {
+            Ok(id) => id,
+            Err(e) => {
+                return SweBenchRunOutcome {
+                    model_patch: String::new(),
+                    proposed_patches: Vec::new(),
+                    failed_stage: Some(RunStage::Prepare),
+                    error: Some(e),
+                };
+            }
+        };
 
-        docker_manager.start_container(&container_id).await?;
+        let prepare_result: DgmResult<()> = async {
+            docker_manager.start_container(&container_id).await?;
+            Self::copy_dgm_files_to_container(docker_manager, &container_id).await?;
+            Ok(())
+        }.await;
+
+        let exec_result = match prepare_result {
+            Ok(()) => Self::setup_and_run_swe_bench_agent(
+                &container_id,
+                entry,
+                model_patch_paths,
+                docker_manager,
+                output_dir,
+                metrics,
+            ).await,
+            Err(e) => Err((RunStage::Prepare, e)),
+        };
 
-        // Copy necessary files to container
-        Self::copy_dgm_files_to_container(docker_manager, &container_id).await?;
+        // Collect: gather whatever artifacts exist regardless of how far Setup/Execute got.
+        metrics.set_stage(&entry.instance_id, RunStage::Collect.to_string().as_str());
+        let (model_patch, proposed_patches) =
+            Self::collect_swe_bench_artifacts(docker_manager, &container_id, entry, output_dir).await;
+
+        // Teardown: always attempt to stop and remove the container.
+        metrics.set_stage(&entry.instance_id, RunStage::Teardown.to_string().as_str());
+        Self::cleanup_container(docker_manager, &container_id).await;
+
+        match exec_result {
+            Ok(()) => SweBenchRunOutcome {
+                model_patch,
+                proposed_patches,
+                failed_stage: None,
+                error: None,
+            },
+            Err((stage, error)) => SweBenchRunOutcome {
+                model_patch,
+                proposed_patches,
+                failed_stage: Some(stage),
+                error: Some(error),
+            },
+        }
+    }
+
+    /// Setup (apply patches + install requirements) and Execute (run the coding agent) stages
+    /// for a SWE-bench container, tagging any failure with which of the two stages it occurred in.
+    async fn setup_and_run_swe_bench_agent(
+        container_id: &str,
+        entry: &SWEBenchEntry,
+        model_patch_paths: Option<Vec<PathBuf>>,
+        docker_manager: &DockerManager,
+        output_dir: &Path,
+        metrics: &EvaluationMetrics,
+    ) -> Result<(), (RunStage, anyhow::Error)> {
+        metrics.set_stage(&entry.instance_id, RunStage::Setup.to_string().as_str());
 
-        // Apply model patches if provided
         if let Some(patch_paths) = model_patch_paths {
             for patch_path in patch_paths {
-                Self::apply_model_patch_to_container(docker_manager, &container_id, &patch_path).await?;
+                Self::apply_model_patch_to_container(docker_manager, container_id, &patch_path)
+                    .await
+                    .map_err(|e| (RunStage::Setup, e))?;
             }
         }
 
-        // Install requirements
         docker_manager.exec_command(
-            &container_id,
+            container_id,
             &["python", "-m", "pip", "install", "-r", "/dgm/requirements.txt"],
             Some(300), // 5 minute timeout
-        ).await?;
+        ).await.map_err(|e| (RunStage::Setup, e))?;
+
+        metrics.set_stage(&entry.instance_id, RunStage::Execute.to_string().as_str());
 
-        // Run the coding agent
         let agent_cmd = vec![
             "timeout", "32400", // 9 hour timeout
             "python", "/dgm/coding_agent.py",
@@ -260,24 +512,37 @@ impl EvaluationHarness {
             "--instance_id", &entry.instance_id,
         ];
 
-        let (_output, exit_code) = docker_manager.exec_command(
-            &container_id,
+        // Stream output live instead of only getting it back after the (possibly 9-hour) process
+        // exits, so a mid-run crash doesn't lose all partial progress.
+        let exit_code = Self::stream_agent_output(
+            docker_manager,
+            container_id,
             &agent_cmd.iter().map(|s| *s).collect::<Vec<_>>(),
-            Some(32400), // 9 hour timeout
-        ).await?;
+            &entry.instance_id,
+            output_dir,
+        ).await.map_err(|e| (RunStage::Execute, e))?;
 
         info!("Agent execution completed with exit code: {}", exit_code);
+        Ok(())
+    }
 
-        // Get model patch
+    /// Collect stage for a SWE-bench container: gathers `model_patch.diff`, any
+    /// `model_patch_*.diff` proposals, and the chat history. Best-effort throughout so a missing
+    /// artifact (e.g. because the agent crashed before writing it) never turns into an error here.
+    async fn collect_swe_bench_artifacts(
+        docker_manager: &DockerManager,
+        container_id: &str,
+        entry: &SWEBenchEntry,
+        output_dir: &Path,
+    ) -> (String, Vec<String>) {
         let (model_patch, _) = docker_manager.exec_command(
-            &container_id,
+            container_id,
             &["cat", "/dgm/model_patch.diff"],
             Some(30),
         ).await.unwrap_or_else(|_| (String::new(), 1));
 
-        // Get proposed model patches
         let (patch_files_output, _) = docker_manager.exec_command(
-            &container_id,
+            container_id,
             &["find", "/dgm/", "-name", "model_patch_*.diff"],
             Some(30),
         ).await.unwrap_or_else(|_| (String::new(), 1));
@@ -286,7 +551,7 @@ impl EvaluationHarness {
         for patch_file in patch_files_output.lines() {
             if !patch_file.trim().is_empty() {
                 let (patch_content, _) = docker_manager.exec_command(
-                    &container_id,
+                    container_id,
                     &["cat", patch_file.trim()],
                     Some(30),
                 ).await.unwrap_or_else(|_| (String::new(), 1));
@@ -294,27 +559,18 @@ impl EvaluationHarness {
             }
         }
 
-        // Copy output files back to host
         let chat_history_container = format!("/dgm/{}.md", entry.instance_id);
         let chat_history_host = output_dir.join(format!("{}.md", entry.instance_id));
 
         if let Err(e) = docker_manager.copy_from_container(
-            &container_id,
+            container_id,
             Path::new(&chat_history_container),
             &chat_history_host,
         ).await {
             warn!("Failed to copy chat history: {}", e);
         }
 
-        // Cleanup container
-        if let Err(e) = docker_manager.stop_container(&container_id, 10).await {
-            warn!("Failed to stop container: {}", e);
-        }
-        if let Err(e) = docker_manager.remove_container(&container_id, true).await {
-            warn!("Failed to remove container: {}", e);
-        }
-
-        Ok((model_patch, proposed_patches))
+        (model_patch, proposed_patches)
     }
 
     /// Get environment variables for container execution
@@ -388,6 +644,62 @@ impl EvaluationHarness {
         Ok(())
     }
 
+    /// Run `command` in `container_id`, forwarding each output line to `tracing` (tagged with
+    /// `instance_id` as a span field) and appending it to `<output_dir>/<instance_id>.log` as it
+    /// arrives. Unlike a plain [`DockerManager::exec_command`] call, this gives live visibility
+    /// into a multi-hour agent run and keeps the partial log even if the container is later
+    /// killed by the `timeout` wrapper around the command.
+    async fn stream_agent_output(
+        docker_manager: &DockerManager,
+        container_id: &str,
+        command: &[&str],
+        instance_id: &str,
+        output_dir: &Path,
+    ) -> DgmResult<i64> {
+        let log_path = output_dir.join(format!("{}.log", instance_id));
+        let mut log_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .await
+            .with_context(|| format!("Failed to open agent log file for {}", instance_id))?;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        let consume = async move {
+            while let Some(line) = receiver.recv().await {
+                let tagged = format!(
+                    "[{}] {}: {}",
+                    line.timestamp.to_rfc3339(),
+                    match &line.channel {
+                        LogChannel::Stdout(_) => "stdout",
+                        LogChannel::Stderr(_) => "stderr",
+                        LogChannel::Progress(_) => "progress",
+                    },
+                    line.text(),
+                );
+
+                match &line.channel {
+                    LogChannel::Stderr(_) => warn!("{}", tagged),
+                    LogChannel::Progress(_) => info!("{}", tagged),
+                    LogChannel::Stdout(_) => debug!("{}", tagged),
+                }
+
+                if let Err(e) = log_file.write_all(format!("{}\n", tagged).as_bytes()).await {
+                    warn!("Failed to append to agent log for {}: {}", instance_id, e);
+                }
+            }
+        }
+        .instrument(tracing::info_span!("agent_run", instance_id = %instance_id));
+
+        let (exit_code, _) = tokio::join!(
+            docker_manager.exec_command_with_sender(container_id, command, Some(32400), sender),
+            consume
+        );
+
+        exit_code
+    }
+
     /// Apply model patch to container
     async fn apply_model_patch_to_container(
         docker_manager: &DockerManager,
@@ -432,59 +744,78 @@ impl EvaluationHarness {
         fs::create_dir_all(output_dir).await
             .context("Failed to create output directory")?;
 
-        // Create semaphore to limit concurrent workers
-        let semaphore = Arc::new(Semaphore::new(self.max_workers));
-
-        // Process entries in parallel
-        let tasks: Vec<_> = entries
-            .into_iter()
-            .map(|entry| {
-                let semaphore = semaphore.clone();
-                let docker_manager = self.docker_manager.clone();
-                let config = self.config.clone();
-                let model_name = model_name_or_path.to_string();
+        // Resume support: instances already recorded in the checkpoint are dropped from the
+        // work list entirely instead of re-entering the scheduler, so a crashed or cancelled run
+        // picks up without re-running any container that already finished.
+        let checkpoint_path = output_dir.join("checkpoint.jsonl");
+        let checkpointed_results = Checkpoint::load(&checkpoint_path).await?;
+        let already_done: HashSet<String> =
+            checkpointed_results.iter().map(|r| r.instance_id.clone()).collect();
+        let entries: Vec<PolyglotEntry> =
+            entries.into_iter().filter(|e| !already_done.contains(&e.instance_id)).collect();
+        if !already_done.is_empty() {
+            info!(
+                "Resuming from checkpoint: {} instances already completed, {} remaining",
+                already_done.len(),
+                entries.len()
+            );
+        }
+        let checkpoint = Arc::new(Mutex::new(Checkpoint::open(&checkpoint_path).await?));
+
+        // Resolve the distinct set of images this run will need and make sure they all exist
+        // before submitting any entry, instead of failing on a missing image deep into the run.
+        let image_names = entries
+            .iter()
+            .map(|entry| format!("polyglot_{}", entry.language));
+        Self::ensure_images_available(&self.scheduler, image_names).await?;
+
+        self.metrics.set_total(already_done.len() + entries.len());
+
+        // Run entries in batches sized off the configured worker count (available CPUs if
+        // unset), bounded by a semaphore so at most that many containers are ever in flight at
+        // once. Each task still submits through the scheduler so it lands on whichever
+        // configured Docker endpoint is currently least loaded.
+        let batch_runner = BatchRunner::new(self.worker_count());
+        let scheduler = self.scheduler.clone();
+        let config = self.config.clone();
+        let metrics = self.metrics.clone();
+        let model_name = model_name_or_path.to_string();
+        let output_path = output_dir.to_path_buf();
+
+        let new_results = batch_runner
+            .run(entries, move |entry| {
+                let scheduler = scheduler.clone();
+                let config = config.clone();
+                let metrics = metrics.clone();
+                let model_name = model_name.clone();
                 let model_patches = model_patch_paths.clone();
-                let output_path = output_dir.to_path_buf();
-
-                tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.unwrap();
-                    Self::process_polyglot_entry(
-                        entry,
-                        &output_path,
-                        &model_name,
-                        model_patches,
-                        docker_manager,
-                        config,
-                    ).await
-                })
+                let output_path = output_path.clone();
+                let checkpoint = checkpoint.clone();
+
+                async move {
+                    scheduler
+                        .submit(|docker_manager| async move {
+                            Self::process_polyglot_entry(
+                                entry,
+                                &output_path,
+                                &model_name,
+                                model_patches,
+                                docker_manager,
+                                config,
+                                metrics,
+                                checkpoint,
+                            ).await
+                        })
+                        .await
+                }
             })
-            .collect();
+            .await;
 
-        // Wait for all tasks to complete
-        let results = join_all(tasks).await;
+        let mut results = checkpointed_results;
+        results.extend(new_results);
 
-        // Collect results
-        let mut evaluation_results = Vec::new();
-        for result in results {
-            match result {
-                Ok(eval_result) => evaluation_results.push(eval_result),
-                Err(e) => {
-                    error!("Task failed: {}", e);
-                    evaluation_results.push(EvaluationResult {
-                        instance_id: "unknown".to_string(),
-                        model_name_or_path: model_name_or_path.to_string(),
-                        model_patch: String::new(),
-                        proposed_model_patches: Vec::new(),
-                        eval_result: "error".to_string(),
-                        success: false,
-                        error: Some(e.to_string()),
-                    });
-                }
-            }
-        }
-
-        info!("Polyglot evaluation completed with {} results", evaluation_results.len());
-        Ok(evaluation_results)
+        info!("Polyglot evaluation completed with {} results", results.len());
+        Ok(results)
     }
 
     /// Process a single Polyglot entry
@@ -495,6 +826,8 @@ impl EvaluationHarness {
         model_patch_paths: Option<Vec<PathBuf>>,
         docker_manager: Arc<DockerManager>,
         config: DgmConfig,
+        metrics: Arc<EvaluationMetrics>,
+        checkpoint: Arc<Mutex<Checkpoint>>,
     ) -> EvaluationResult {
         let instance_id = &entry.instance_id;
         info!("Processing Polyglot entry: {}", instance_id);
@@ -505,11 +838,14 @@ impl EvaluationHarness {
             info!("Skipping existing entry: {}", instance_id);
             if let Ok(content) = fs::read_to_string(&result_file).await {
                 if let Ok(result) = serde_json::from_str::<EvaluationResult>(&content) {
+                    metrics.finish_instance(instance_id, result.success);
                     return result;
                 }
             }
         }
 
+        metrics.start_instance(instance_id, RunStage::Prepare.to_string().as_str());
+
         let mut result = EvaluationResult {
             instance_id: instance_id.clone(),
             model_name_or_path: model_name_or_path.to_string(),
@@ -518,33 +854,47 @@ impl EvaluationHarness {
             eval_result: "incomplete".to_string(),
             success: false,
             error: None,
+            failed_stage: None,
+            test_results: None,
         };
 
         // Create container name with timestamp
         let run_id = chrono::Utc::now().format("%Y%m%d_%H%M%S_%f").to_string();
         let container_name = format!("polyglot_{}_{}", instance_id, run_id);
 
-        match Self::run_polyglot_container(
+        let outcome = Self::run_polyglot_container(
             &entry,
             &container_name,
             model_patch_paths,
             &docker_manager,
             &config,
             output_dir,
-        ).await {
-            Ok((model_patch, eval_result)) => {
-                result.model_patch = model_patch;
-                result.eval_result = eval_result;
+            &metrics,
+        ).await;
+
+        result.model_patch = outcome.model_patch;
+        result.eval_result = outcome.eval_result;
+        result.test_results = outcome.test_results;
+        result.failed_stage = outcome.failed_stage;
+
+        match outcome.error {
+            None => {
                 result.success = true;
                 info!("Successfully processed Polyglot entry: {}", instance_id);
             }
-            Err(e) => {
+            Some(e) => {
                 result.error = Some(e.to_string());
-                result.eval_result = "error".to_string();
-                error!("Failed to process Polyglot entry {}: {}", instance_id, e);
+                error!(
+                    "Failed to process Polyglot entry {} at stage {}: {}",
+                    instance_id,
+                    outcome.failed_stage.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+                    e
+                );
             }
         }
 
+        metrics.finish_instance(instance_id, result.success);
+
         // Save result to file
         if let Ok(json_content) = serde_json::to_string_pretty(&result) {
             if let Err(e) = fs::write(&result_file, json_content).await {
@@ -552,10 +902,16 @@ impl EvaluationHarness {
             }
         }
 
+        if let Err(e) = checkpoint.lock().await.append(&result).await {
+            warn!("Failed to append checkpoint for {}: {}", instance_id, e);
+        }
+
         result
     }
 
-    /// Run Polyglot evaluation in a Docker container
+    /// Run Polyglot evaluation in a Docker container, staged the same way as
+    /// [`Self::run_swe_bench_container`]: Prepare -> Setup -> Execute -> Collect -> Teardown,
+    /// with Collect and Teardown always running once the container exists.
     async fn run_polyglot_container(
         entry: &PolyglotEntry,
         container_name: &str,
@@ -563,38 +919,110 @@ impl EvaluationHarness {
         docker_manager: &DockerManager,
         _config: &DgmConfig,
         output_dir: &Path,
-    ) -> DgmResult<(String, String)> {
-        // Build or get the appropriate Docker image for this entry
+        metrics: &EvaluationMetrics,
+    ) -> PolyglotRunOutcome {
         let image_name = format!("polyglot_{}", entry.language);
 
-        // Create and start container
-        let container_id = docker_manager.create_container(
+        // Prepare: create the container. If this fails there is nothing to collect or tear down.
+        let container_id = match docker_manager.create_container(
             &image_name,
             container_name,
             Some("/testbed"),
             Some(Self::get_environment_variables()),
-        ).await?;
+        ).await {
+            Ok(id) => id,
+            Err(e) => {
+                return PolyglotRunOutcome {
+                    model_patch: String::new(),
+                    eval_result: "error".to_string(),
+                    test_results: None,
+                    failed_stage: Some(RunStage::Prepare),
+                    error: Some(e),
+                };
+            }
+        };
+
+        let prepare_result: DgmResult<()> = async {
+            docker_manager.start_container(&container_id).await?;
+            Self::copy_dgm_files_to_container(docker_manager, &container_id).await?;
+            Ok(())
+        }.await;
+
+        let exec_result = match prepare_result {
+            Ok(()) => Self::setup_and_run_polyglot_agent(
+                &container_id,
+                entry,
+                model_patch_paths,
+                docker_manager,
+                metrics,
+            ).await,
+            Err(e) => Err((RunStage::Prepare, e)),
+        };
+
+        // Collect: always attempt to gather whatever patch and chat history exist, even if Setup
+        // or Execute failed, so a crash never silently drops a partial patch.
+        metrics.set_stage(&entry.instance_id, RunStage::Collect.to_string().as_str());
+        let (model_patch, collect_result) =
+            Self::collect_polyglot_result(docker_manager, &container_id, entry, output_dir).await;
 
-        docker_manager.start_container(&container_id).await?;
+        // Teardown: always attempt to stop and remove the container.
+        metrics.set_stage(&entry.instance_id, RunStage::Teardown.to_string().as_str());
+        Self::cleanup_container(docker_manager, &container_id).await;
 
-        // Copy necessary files to container
-        Self::copy_dgm_files_to_container(docker_manager, &container_id).await?;
+        match exec_result {
+            Err((stage, error)) => PolyglotRunOutcome {
+                model_patch,
+                eval_result: "error".to_string(),
+                test_results: None,
+                failed_stage: Some(stage),
+                error: Some(error),
+            },
+            Ok(()) => match collect_result {
+                Ok((eval_result, test_results)) => PolyglotRunOutcome {
+                    model_patch,
+                    eval_result,
+                    test_results,
+                    failed_stage: None,
+                    error: None,
+                },
+                Err(e) => PolyglotRunOutcome {
+                    model_patch,
+                    eval_result: "error".to_string(),
+                    test_results: None,
+                    failed_stage: Some(RunStage::Collect),
+                    error: Some(e),
+                },
+            },
+        }
+    }
+
+    /// Setup (apply patches + install requirements) and Execute (run the coding agent) stages
+    /// for a Polyglot container, tagging any failure with which of the two stages it occurred in.
+    async fn setup_and_run_polyglot_agent(
+        container_id: &str,
+        entry: &PolyglotEntry,
+        model_patch_paths: Option<Vec<PathBuf>>,
+        docker_manager: &DockerManager,
+        metrics: &EvaluationMetrics,
+    ) -> Result<(), (RunStage, anyhow::Error)> {
+        metrics.set_stage(&entry.instance_id, RunStage::Setup.to_string().as_str());
 
-        // Apply model patches if provided
         if let Some(patch_paths) = model_patch_paths {
             for patch_path in patch_paths {
-                Self::apply_model_patch_to_container(docker_manager, &container_id, &patch_path).await?;
+                Self::apply_model_patch_to_container(docker_manager, container_id, &patch_path)
+                    .await
+                    .map_err(|e| (RunStage::Setup, e))?;
             }
         }
 
-        // Install requirements
         docker_manager.exec_command(
-            &container_id,
+            container_id,
             &["python", "-m", "pip", "install", "-r", "/dgm/requirements.txt"],
             Some(300), // 5 minute timeout
-        ).await?;
+        ).await.map_err(|e| (RunStage::Setup, e))?;
+
+        metrics.set_stage(&entry.instance_id, RunStage::Execute.to_string().as_str());
 
-        // Run the coding agent
         let agent_cmd = vec![
             "timeout", "600", // 10 minute timeout
             "python", "/dgm/coding_agent.py",
@@ -606,82 +1034,102 @@ impl EvaluationHarness {
         ];
 
         let (_output, exit_code) = docker_manager.exec_command(
-            &container_id,
+            container_id,
             &agent_cmd.iter().map(|s| *s).collect::<Vec<_>>(),
             Some(600), // 10 minute timeout
-        ).await?;
+        ).await.map_err(|e| (RunStage::Execute, e))?;
 
         info!("Agent execution completed with exit code: {}", exit_code);
+        Ok(())
+    }
 
-        // Get model patch
+    /// Collect stage for a Polyglot container: gathers `model_patch.diff` and, if it's non-empty,
+    /// resets the testbed to the test commit and runs the language-specific test suite to produce
+    /// the final `eval_result`. Also copies the chat history back to the host. An empty patch is
+    /// not a failure — it simply yields `"empty_patch"`.
+    async fn collect_polyglot_result(
+        docker_manager: &DockerManager,
+        container_id: &str,
+        entry: &PolyglotEntry,
+        output_dir: &Path,
+    ) -> (String, Result<(String, Option<HashMap<String, bool>>), anyhow::Error>) {
         let (model_patch, _) = docker_manager.exec_command(
-            &container_id,
+            container_id,
             &["cat", "/dgm/model_patch.diff"],
             Some(30),
         ).await.unwrap_or_else(|_| (String::new(), 1));
 
-        // If no patch was generated, return early
         if model_patch.trim().is_empty() {
-            // Cleanup container
-            Self::cleanup_container(docker_manager, &container_id).await;
-            return Ok((model_patch, "empty_patch".to_string()));
+            return (model_patch, Ok(("empty_patch".to_string(), None)));
         }
 
-        // Stash solution files and reset to test commit
-        let stash_files = entry.files.solution.join(" ");
-        docker_manager.exec_command(
-            &container_id,
-            &["git", "-C", "/testbed", "stash", "push", &stash_files],
-            Some(60),
-        ).await?;
+        let eval_result = async {
+            let stash_files = entry.files.solution.join(" ");
+            docker_manager.exec_command(
+                container_id,
+                &["git", "-C", "/testbed", "stash", "push", &stash_files],
+                Some(60),
+            ).await?;
+
+            docker_manager.exec_command(
+                container_id,
+                &["git", "-C", "/testbed", "reset", "--hard", &entry.test_commit],
+                Some(60),
+            ).await?;
+
+            docker_manager.exec_command(
+                container_id,
+                &["git", "-C", "/testbed", "clean", "-fd"],
+                Some(60),
+            ).await?;
+
+            docker_manager.exec_command(
+                container_id,
+                &["git", "-C", "/testbed", "stash", "pop"],
+                Some(60),
+            ).await?;
+
+            Self::run_language_evaluation(
+                docker_manager,
+                container_id,
+                &entry.language,
+                entry.expected_output.as_ref(),
+                &entry.fail_to_pass,
+                &entry.pass_to_pass,
+            ).await
+        }.await;
 
-        docker_manager.exec_command(
-            &container_id,
-            &["git", "-C", "/testbed", "reset", "--hard", &entry.test_commit],
-            Some(60),
-        ).await?;
-
-        docker_manager.exec_command(
-            &container_id,
-            &["git", "-C", "/testbed", "clean", "-fd"],
-            Some(60),
-        ).await?;
-
-        docker_manager.exec_command(
-            &container_id,
-            &["git", "-C", "/testbed", "stash", "pop"],
-            Some(60),
-        ).await?;
-
-        // Run evaluation based on language
-        let eval_result = Self::run_language_evaluation(docker_manager, &container_id, &entry.language).await?;
-
-        // Copy output files back to host
         let chat_history_container = format!("/dgm/{}.md", entry.instance_id);
         let chat_history_host = output_dir.join(format!("{}.md", entry.instance_id));
 
         if let Err(e) = docker_manager.copy_from_container(
-            &container_id,
+            container_id,
             Path::new(&chat_history_container),
             &chat_history_host,
         ).await {
             warn!("Failed to copy chat history: {}", e);
         }
 
-        // Cleanup container
-        Self::cleanup_container(docker_manager, &container_id).await;
-
-        Ok((model_patch, eval_result))
+        (model_patch, eval_result)
     }
 
-    /// Run language-specific evaluation
+    /// Run language-specific evaluation. When `fail_to_pass`/`pass_to_pass` are non-empty, the
+    /// instance is `"resolved"` only if every named test passed according to that language's
+    /// result parser (languages without one, e.g. javascript/cpp, can never resolve this way).
+    /// Otherwise this falls back to the previous behavior: `"resolved"` when the exit code is
+    /// zero and (if `expected_output` is given) every listed fd's captured output matches its
+    /// regex. An invalid regex is surfaced as an error rather than silently treated as a pass.
+    /// Returns the per-test outcomes alongside the eval result, for [`EvaluationResult::test_results`].
     async fn run_language_evaluation(
         docker_manager: &DockerManager,
         container_id: &str,
         language: &str,
-    ) -> DgmResult<String> {
+        expected_output: Option<&HashMap<u8, String>>,
+        fail_to_pass: &[String],
+        pass_to_pass: &[String],
+    ) -> DgmResult<(String, Option<HashMap<String, bool>>)> {
         let test_command = match language {
-            "python" => vec!["python", "-m", "pytest", "-xvs"],
+            "python" => vec!["python", "-m", "pytest", "-vs"],
             "javascript" => vec!["npm", "test"],
             "java" => vec!["mvn", "test"],
             "cpp" => vec!["make", "test"],
@@ -689,17 +1137,147 @@ impl EvaluationHarness {
             _ => vec!["echo", "No test command defined for language"],
         };
 
-        let (_output, exit_code) = docker_manager.exec_command(
+        let (stdout, stderr, exit_code) = docker_manager.exec_command_split(
             container_id,
             &test_command,
             Some(120), // 2 minute timeout
-        ).await.unwrap_or_else(|_| (String::new(), 1));
+        ).await.unwrap_or_else(|_| (String::new(), String::new(), 1));
 
-        if exit_code == 0 {
-            Ok("resolved".to_string())
-        } else {
-            Ok("unresolved".to_string())
+        let test_results = match language {
+            "python" => Some(Self::parse_pytest_results(&stdout)),
+            "rust" => Some(Self::parse_cargo_test_results(&stdout)),
+            "java" => Some(Self::collect_junit_results(docker_manager, container_id).await),
+            _ => None,
+        };
+
+        if !fail_to_pass.is_empty() || !pass_to_pass.is_empty() {
+            // No per-test parser for this language: can't verify fail_to_pass/pass_to_pass by name.
+            let resolved = test_results.as_ref().map_or(false, |results| {
+                fail_to_pass.iter().all(|t| results.get(t).copied().unwrap_or(false))
+                    && pass_to_pass.iter().all(|t| results.get(t).copied().unwrap_or(false))
+            });
+
+            let eval_result = if resolved { "resolved" } else { "unresolved" };
+            return Ok((eval_result.to_string(), test_results));
+        }
+
+        if exit_code != 0 {
+            return Ok(("unresolved".to_string(), test_results));
+        }
+
+        let Some(expected_output) = expected_output.filter(|m| !m.is_empty()) else {
+            return Ok(("resolved".to_string(), test_results));
+        };
+
+        for (fd, pattern) in expected_output {
+            let captured = match fd {
+                1 => &stdout,
+                2 => &stderr,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Unsupported expected_output fd {} (only 1=stdout and 2=stderr are captured)",
+                        fd
+                    ));
+                }
+            };
+
+            let regex = regex::Regex::new(pattern)
+                .with_context(|| format!("Invalid expected_output regex for fd {}: '{}'", fd, pattern))?;
+
+            if !regex.is_match(captured) {
+                return Ok(("unresolved".to_string(), test_results));
+            }
         }
+
+        Ok(("resolved".to_string(), test_results))
+    }
+
+    /// Parse pytest `-vs` output for individual test outcomes, keyed by pytest's own node id
+    /// (e.g. `tests/test_foo.py::test_bar`). Lines that aren't a result line are ignored.
+    fn parse_pytest_results(output: &str) -> HashMap<String, bool> {
+        let line_re = regex::Regex::new(r"^(\S+::\S+)\s+(PASSED|FAILED)\b")
+            .expect("static pytest result regex is valid");
+
+        let mut results = HashMap::new();
+        for line in output.lines() {
+            if let Some(cap) = line_re.captures(line.trim()) {
+                results.insert(cap[1].to_string(), &cap[2] == "PASSED");
+            }
+        }
+        results
+    }
+
+    /// Parse `cargo test` output for individual test outcomes, keyed by the test's module path
+    /// (e.g. `tests::foo`). Lines that aren't a `test ... ok`/`FAILED` result line are ignored.
+    fn parse_cargo_test_results(output: &str) -> HashMap<String, bool> {
+        let line_re = regex::Regex::new(r"^test (\S+) \.\.\. (ok|FAILED)")
+            .expect("static cargo test result regex is valid");
+
+        let mut results = HashMap::new();
+        for line in output.lines() {
+            if let Some(cap) = line_re.captures(line.trim()) {
+                results.insert(cap[1].to_string(), &cap[2] == "ok");
+            }
+        }
+        results
+    }
+
+    /// Find and parse every JUnit XML report `mvn test` wrote under `surefire-reports`, across
+    /// the whole test tree (multi-module Maven projects scatter them under each module).
+    async fn collect_junit_results(
+        docker_manager: &DockerManager,
+        container_id: &str,
+    ) -> HashMap<String, bool> {
+        let (find_output, _, _) = docker_manager.exec_command_split(
+            container_id,
+            &["find", ".", "-path", "*/surefire-reports/*.xml"],
+            Some(30),
+        ).await.unwrap_or_else(|_| (String::new(), String::new(), 1));
+
+        let mut xml = String::new();
+        for report_path in find_output.lines() {
+            let report_path = report_path.trim();
+            if report_path.is_empty() {
+                continue;
+            }
+            let (content, _, _) = docker_manager.exec_command_split(
+                container_id,
+                &["cat", report_path],
+                Some(30),
+            ).await.unwrap_or_else(|_| (String::new(), String::new(), 1));
+            xml.push_str(&content);
+            xml.push('\n');
+        }
+
+        Self::parse_junit_results(&xml)
+    }
+
+    /// Parse JUnit XML `<testcase>` elements into `classname.name -> passed`, where `passed` is
+    /// false only if the element has a `<failure>`/`<error>` child. No XML parser dependency is
+    /// pulled in for this — the element shape is narrow enough that a couple of targeted regexes
+    /// cover it, consistent with how `expected_output` already does regex-based output matching.
+    fn parse_junit_results(xml: &str) -> HashMap<String, bool> {
+        let testcase_re = regex::Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)")
+            .expect("static JUnit testcase regex is valid");
+        let name_re = regex::Regex::new(r#"name="([^"]*)""#).expect("static regex is valid");
+        let classname_re = regex::Regex::new(r#"classname="([^"]*)""#).expect("static regex is valid");
+
+        let mut results = HashMap::new();
+        for cap in testcase_re.captures_iter(xml) {
+            let attrs = &cap[1];
+            let Some(name) = name_re.captures(attrs).map(|c| c[1].to_string()) else {
+                continue;
+            };
+            let key = match classname_re.captures(attrs) {
+                Some(c) => format!("{}.{}", &c[1], name),
+                None => name,
+            };
+
+            let body = cap.get(2).map(|m| m.as_str()).unwrap_or("");
+            let passed = !body.contains("<failure") && !body.contains("<error");
+            results.insert(key, passed);
+        }
+        results
     }
 
     /// Cleanup container
@@ -726,6 +1304,37 @@ impl EvaluationHarness {
         Ok(dataset)
     }
 
+    /// Export `results` to `path` as a JSON array, in the same shape [`Self::load_dataset`] and
+    /// [`Self::import_results`] expect, so a full or partial run's results can be copied off one
+    /// machine and merged with others.
+    pub async fn export_results(results: &[EvaluationResult], path: &Path) -> DgmResult<()> {
+        let json_content = serde_json::to_string_pretty(results)
+            .context("Failed to serialize results for export")?;
+
+        fs::write(path, json_content).await
+            .with_context(|| format!("Failed to write exported results to {:?}", path))?;
+
+        Ok(())
+    }
+
+    /// Import and merge results exported by [`Self::export_results`] (or written by a checkpoint
+    /// converted via [`Self::load_dataset`]) from one or more `paths`. When the same
+    /// `instance_id` appears in more than one file, the later path in `paths` wins - e.g. a
+    /// second machine's re-run of an instance that errored on the first.
+    pub async fn import_results(paths: &[PathBuf]) -> DgmResult<Vec<EvaluationResult>> {
+        let mut merged: HashMap<String, EvaluationResult> = HashMap::new();
+
+        for path in paths {
+            let results: Vec<EvaluationResult> = Self::load_dataset(path).await
+                .with_context(|| format!("Failed to import results from {:?}", path))?;
+            for result in results {
+                merged.insert(result.instance_id.clone(), result);
+            }
+        }
+
+        Ok(merged.into_values().collect())
+    }
+
     /// Generate evaluation report
     pub fn generate_report(results: &[EvaluationResult]) -> HashMap<String, serde_json::Value> {
         let total_instances = results.len();
@@ -734,6 +1343,9 @@ impl EvaluationHarness {
         let unresolved_instances = results.iter().filter(|r| r.eval_result == "unresolved").count();
         let empty_patch_instances = results.iter().filter(|r| r.eval_result == "empty_patch").count();
         let error_instances = results.iter().filter(|r| r.eval_result == "error").count();
+        // Fail_to_pass/pass_to_pass scoring on a mixed test suite: some named tests passed, some
+        // didn't, so the instance is neither a clean "resolved" nor a total miss.
+        let partially_resolved_instances = results.iter().filter(|r| Self::is_partially_resolved(r)).count();
 
         let mut report = HashMap::new();
         report.insert("total_instances".to_string(), serde_json::Value::Number(total_instances.into()));
@@ -742,6 +1354,7 @@ impl EvaluationHarness {
         report.insert("unresolved_instances".to_string(), serde_json::Value::Number(unresolved_instances.into()));
         report.insert("empty_patch_instances".to_string(), serde_json::Value::Number(empty_patch_instances.into()));
         report.insert("error_instances".to_string(), serde_json::Value::Number(error_instances.into()));
+        report.insert("partially_resolved_instances".to_string(), serde_json::Value::Number(partially_resolved_instances.into()));
 
         // Add ID lists
         let completed_ids: Vec<_> = results.iter().filter(|r| r.success).map(|r| &r.instance_id).collect();
@@ -749,13 +1362,136 @@ impl EvaluationHarness {
         let unresolved_ids: Vec<_> = results.iter().filter(|r| r.eval_result == "unresolved").map(|r| &r.instance_id).collect();
         let empty_patch_ids: Vec<_> = results.iter().filter(|r| r.eval_result == "empty_patch").map(|r| &r.instance_id).collect();
         let error_ids: Vec<_> = results.iter().filter(|r| r.eval_result == "error").map(|r| &r.instance_id).collect();
+        let partially_resolved_ids: Vec<_> =
+            results.iter().filter(|r| Self::is_partially_resolved(r)).map(|r| &r.instance_id).collect();
 
         report.insert("completed_ids".to_string(), serde_json::to_value(completed_ids).unwrap());
         report.insert("resolved_ids".to_string(), serde_json::to_value(resolved_ids).unwrap());
         report.insert("unresolved_ids".to_string(), serde_json::to_value(unresolved_ids).unwrap());
         report.insert("empty_patch_ids".to_string(), serde_json::to_value(empty_patch_ids).unwrap());
         report.insert("error_ids".to_string(), serde_json::to_value(error_ids).unwrap());
+        report.insert("partially_resolved_ids".to_string(), serde_json::to_value(partially_resolved_ids).unwrap());
 
         report
     }
+
+    /// True when `result.test_results` shows a mix of passing and failing tests - i.e. the
+    /// parsed test suite didn't fully pass, but wasn't a total loss either. Every parsed test
+    /// counts, not only the ones named in `fail_to_pass`/`pass_to_pass`.
+    fn is_partially_resolved(result: &EvaluationResult) -> bool {
+        result.test_results.as_ref().map_or(false, |tests| {
+            let passed = tests.values().filter(|passed| **passed).count();
+            passed > 0 && passed < tests.len()
+        })
+    }
+
+    /// Render an evaluation report in `format`. `Json` is [`Self::generate_report`] pretty-printed;
+    /// `JunitXml` and `Markdown` exist so a harness run's output can go straight into a CI test
+    /// dashboard or a PR description without a caller having to post-process the JSON shape itself.
+    pub fn render_report(results: &[EvaluationResult], format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => serde_json::to_string_pretty(&Self::generate_report(results))
+                .unwrap_or_else(|e| format!("{{\"error\": \"failed to serialize report: {}\"}}", e)),
+            ReportFormat::JunitXml => Self::render_junit_report(results),
+            ReportFormat::Markdown => Self::render_markdown_report(results),
+        }
+    }
+
+    /// One `<testcase>` per instance, with a `<failure>` for `"unresolved"`/`"error"` instances,
+    /// so CI systems that already understand JUnit XML test reports can surface eval results
+    /// alongside regular test output.
+    fn render_junit_report(results: &[EvaluationResult]) -> String {
+        let failures = results
+            .iter()
+            .filter(|r| r.eval_result == "unresolved" || r.eval_result == "error")
+            .count();
+
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!(
+            "<testsuite name=\"dgm-evaluation\" tests=\"{}\" failures=\"{}\">\n",
+            results.len(),
+            failures
+        ));
+
+        for result in results {
+            out.push_str(&format!(
+                "  <testcase name=\"{}\" classname=\"{}\">\n",
+                xml_escape(&result.instance_id),
+                xml_escape(&result.model_name_or_path),
+            ));
+
+            if result.eval_result == "unresolved" || result.eval_result == "error" {
+                let message = result.error.as_deref().unwrap_or(&result.eval_result);
+                out.push_str(&format!(
+                    "    <failure message=\"{}\">{}</failure>\n",
+                    xml_escape(message),
+                    xml_escape(&result.eval_result),
+                ));
+            }
+
+            out.push_str("  </testcase>\n");
+        }
+
+        out.push_str("</testsuite>\n");
+        out
+    }
+
+    /// A summary table of the tallies [`Self::generate_report`] computes (all but
+    /// `completed_instances`), followed by collapsible `<details>` sections for the resolved,
+    /// unresolved and errored ID lists, sized for pasting straight into a PR.
+    fn render_markdown_report(results: &[EvaluationResult]) -> String {
+        let report = Self::generate_report(results);
+        let count = |key: &str| report.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        let ids = |key: &str| -> Vec<String> {
+            report
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter().filter_map(|id| id.as_str().map(String::from)).collect())
+                .unwrap_or_default()
+        };
+
+        let mut out = String::new();
+        out.push_str("## Evaluation Report\n\n");
+        out.push_str("| Metric | Count |\n");
+        out.push_str("|---|---|\n");
+        out.push_str(&format!("| Total | {} |\n", count("total_instances")));
+        out.push_str(&format!("| Resolved | {} |\n", count("resolved_instances")));
+        out.push_str(&format!("| Unresolved | {} |\n", count("unresolved_instances")));
+        out.push_str(&format!("| Partially resolved | {} |\n", count("partially_resolved_instances")));
+        out.push_str(&format!("| Empty patch | {} |\n", count("empty_patch_instances")));
+        out.push_str(&format!("| Error | {} |\n", count("error_instances")));
+        out.push('\n');
+
+        for (title, key) in [
+            ("Resolved", "resolved_ids"),
+            ("Unresolved", "unresolved_ids"),
+            ("Errored", "error_ids"),
+        ] {
+            let ids = ids(key);
+            out.push_str(&format!("<details>\n<summary>{} ({})</summary>\n\n", title, ids.len()));
+            for id in &ids {
+                out.push_str(&format!("- {}\n", id));
+            }
+            out.push_str("\n</details>\n\n");
+        }
+
+        out
+    }
+}
+
+/// Output format for [`EvaluationHarness::render_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// The original `generate_report` shape, as pretty-printed JSON.
+    Json,
+    /// One `<testcase>` per instance, with a `<failure>` for `"unresolved"`/`"error"` instances.
+    JunitXml,
+    /// A summary table plus collapsible ID lists, for pasting straight into a PR description.
+    Markdown,
+}
+
+/// Escape the characters XML requires escaped in attribute values and element text.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
 }
@@ -0,0 +1,243 @@
+use crate::utils::docker_scheduler::DockerScheduler;
+use crate::DgmResult;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{error, info, warn};
+
+/// One instance currently occupying a container, tracked so the admin server can report its
+/// current [`RunStage`](super::RunStage) and how long it has been running without tailing logs.
+struct InFlightInstance {
+    stage: String,
+    started_at: Instant,
+}
+
+/// Shared counters and per-instance state updated as entries move through
+/// `process_swe_bench_entry` / `process_polyglot_entry`, and read back by the admin HTTP server
+/// started by [`EvaluationHarness::new`](super::EvaluationHarness::new).
+///
+/// All fields are cheap, lock-free atomics except `in_flight_instances`, whose critical section
+/// is a plain `HashMap` insert/remove — never held across an `.await`.
+pub struct EvaluationMetrics {
+    total: AtomicU64,
+    completed: AtomicU64,
+    succeeded: AtomicU64,
+    failed: AtomicU64,
+    in_flight_instances: Mutex<HashMap<String, InFlightInstance>>,
+}
+
+impl EvaluationMetrics {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            total: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            succeeded: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            in_flight_instances: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Set the total instance count for the run currently starting, so `/status` can report
+    /// progress as `completed / total` from the first entry onward.
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total as u64, Ordering::Relaxed);
+    }
+
+    /// Record that `instance_id` has entered a container, initially at `stage`.
+    pub fn start_instance(&self, instance_id: &str, stage: &str) {
+        self.in_flight_instances.lock().unwrap().insert(
+            instance_id.to_string(),
+            InFlightInstance { stage: stage.to_string(), started_at: Instant::now() },
+        );
+    }
+
+    /// Advance `instance_id`'s recorded stage, e.g. "prepare" -> "setup" -> "execute".
+    pub fn set_stage(&self, instance_id: &str, stage: &str) {
+        if let Some(entry) = self.in_flight_instances.lock().unwrap().get_mut(instance_id) {
+            entry.stage = stage.to_string();
+        }
+    }
+
+    /// Record that `instance_id` has left its container, one way or another.
+    pub fn finish_instance(&self, instance_id: &str, success: bool) {
+        self.in_flight_instances.lock().unwrap().remove(instance_id);
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        if success {
+            self.succeeded.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self, scheduler: &DockerScheduler) -> StatusSnapshot {
+        let in_flight = self.in_flight_instances.lock().unwrap();
+        StatusSnapshot {
+            total: self.total.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            succeeded: self.succeeded.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            in_flight: in_flight.len() as u64,
+            endpoints: scheduler
+                .endpoint_status()
+                .into_iter()
+                .map(|e| EndpointSnapshot { name: e.name, in_flight: e.in_flight, capacity: e.capacity })
+                .collect(),
+            instances: in_flight
+                .iter()
+                .map(|(instance_id, entry)| InstanceSnapshot {
+                    instance_id: instance_id.clone(),
+                    stage: entry.stage.clone(),
+                    running_seconds: entry.started_at.elapsed().as_secs_f64(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Render the current state as Prometheus text exposition format.
+    fn render_prometheus(&self, scheduler: &DockerScheduler) -> String {
+        let snapshot = self.snapshot(scheduler);
+        let mut out = String::new();
+
+        out.push_str("# HELP dgm_eval_instances_total Total instances in the current run.\n");
+        out.push_str("# TYPE dgm_eval_instances_total gauge\n");
+        out.push_str(&format!("dgm_eval_instances_total {}\n", snapshot.total));
+
+        out.push_str("# HELP dgm_eval_instances_completed Instances that have finished (success or failure).\n");
+        out.push_str("# TYPE dgm_eval_instances_completed gauge\n");
+        out.push_str(&format!("dgm_eval_instances_completed {}\n", snapshot.completed));
+
+        out.push_str("# HELP dgm_eval_instances_succeeded Instances that finished successfully.\n");
+        out.push_str("# TYPE dgm_eval_instances_succeeded gauge\n");
+        out.push_str(&format!("dgm_eval_instances_succeeded {}\n", snapshot.succeeded));
+
+        out.push_str("# HELP dgm_eval_instances_failed Instances that finished with an error.\n");
+        out.push_str("# TYPE dgm_eval_instances_failed gauge\n");
+        out.push_str(&format!("dgm_eval_instances_failed {}\n", snapshot.failed));
+
+        out.push_str("# HELP dgm_eval_instances_in_flight Instances currently running in a container.\n");
+        out.push_str("# TYPE dgm_eval_instances_in_flight gauge\n");
+        out.push_str(&format!("dgm_eval_instances_in_flight {}\n", snapshot.in_flight));
+
+        out.push_str("# HELP dgm_eval_endpoint_in_flight Containers currently running on this Docker endpoint.\n");
+        out.push_str("# TYPE dgm_eval_endpoint_in_flight gauge\n");
+        for endpoint in &snapshot.endpoints {
+            out.push_str(&format!(
+                "dgm_eval_endpoint_in_flight{{endpoint=\"{}\"}} {}\n",
+                endpoint.name, endpoint.in_flight
+            ));
+        }
+
+        out.push_str("# HELP dgm_eval_endpoint_capacity Maximum concurrent containers on this Docker endpoint.\n");
+        out.push_str("# TYPE dgm_eval_endpoint_capacity gauge\n");
+        for endpoint in &snapshot.endpoints {
+            out.push_str(&format!(
+                "dgm_eval_endpoint_capacity{{endpoint=\"{}\"}} {}\n",
+                endpoint.name, endpoint.capacity
+            ));
+        }
+
+        out.push_str("# HELP dgm_eval_instance_running_seconds Wall-clock time the instance has been running.\n");
+        out.push_str("# TYPE dgm_eval_instance_running_seconds gauge\n");
+        for instance in &snapshot.instances {
+            out.push_str(&format!(
+                "dgm_eval_instance_running_seconds{{instance_id=\"{}\",stage=\"{}\"}} {}\n",
+                instance.instance_id, instance.stage, instance.running_seconds
+            ));
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct EndpointSnapshot {
+    name: String,
+    in_flight: usize,
+    capacity: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct InstanceSnapshot {
+    instance_id: String,
+    stage: String,
+    running_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StatusSnapshot {
+    total: u64,
+    completed: u64,
+    succeeded: u64,
+    failed: u64,
+    in_flight: u64,
+    endpoints: Vec<EndpointSnapshot>,
+    instances: Vec<InstanceSnapshot>,
+}
+
+/// Serve `/status` (JSON) and `/metrics` (Prometheus text) on `bind_address` until the process
+/// exits, so an operator can scrape progress on a long SWE-bench/Polyglot sweep in real time
+/// instead of tailing logs. Runs forever; spawn it and let it ride alongside the evaluation.
+pub async fn serve(
+    bind_address: String,
+    metrics: Arc<EvaluationMetrics>,
+    scheduler: Arc<DockerScheduler>,
+) -> DgmResult<()> {
+    let listener = TcpListener::bind(&bind_address).await?;
+    info!("Evaluation progress server listening on http://{}", bind_address);
+
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Failed to accept progress server connection: {}", e);
+                continue;
+            }
+        };
+
+        let metrics = metrics.clone();
+        let scheduler = scheduler.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(&mut stream, &metrics, &scheduler).await {
+                error!("Error serving progress request: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    stream: &mut tokio::net::TcpStream,
+    metrics: &EvaluationMetrics,
+    scheduler: &DockerScheduler,
+) -> DgmResult<()> {
+    // Only the request line is needed to route `GET /status` and `GET /metrics`; the rest of the
+    // request (headers, body) is read and discarded.
+    let mut buf = vec![0u8; 4096];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let path = request.lines().next().unwrap_or("").split_whitespace().nth(1).unwrap_or("/");
+
+    let (status_line, content_type, body) = match path {
+        "/status" => (
+            "200 OK",
+            "application/json",
+            serde_json::to_string_pretty(&metrics.snapshot(scheduler))?,
+        ),
+        "/metrics" => ("200 OK", "text/plain; version=0.0.4", metrics.render_prometheus(scheduler)),
+        _ => ("404 Not Found", "text/plain", "not found".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_line,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
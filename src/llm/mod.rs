@@ -1,3 +1,4 @@
+use crate::tools::ToolInfo;
 use crate::DgmResult;
 use async_trait::async_trait;
 use backoff::{ExponentialBackoff, Error as BackoffError, future::retry};
@@ -44,15 +45,84 @@ pub const AVAILABLE_LLMS: &[&str] = &[
     "deepseek-reasoner",
 ];
 
+/// Typed view of a [`Message`]'s content. Providers that support native tool calling (Anthropic
+/// `tool_use`/`tool_result` blocks, OpenAI `tool_calls`) round-trip through `ToolCall`/`ToolResult`
+/// instead of being squashed into plain text, so callers can match on this instead of scraping
+/// `<tool_use>` tags back out of a flat string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MessageContent {
+    Text { text: String },
+    ToolCall { id: String, tool_name: String, tool_input: Value },
+    ToolResult { tool_call_id: String, content: String },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: String,
     pub content: Value,
 }
 
+impl Message {
+    pub fn text(role: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: json!(MessageContent::Text { text: text.into() }),
+        }
+    }
+
+    pub fn tool_call(role: impl Into<String>, id: impl Into<String>, tool_name: impl Into<String>, tool_input: Value) -> Self {
+        Self {
+            role: role.into(),
+            content: json!(MessageContent::ToolCall {
+                id: id.into(),
+                tool_name: tool_name.into(),
+                tool_input,
+            }),
+        }
+    }
+
+    pub fn tool_result(role: impl Into<String>, tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: json!(MessageContent::ToolResult {
+                tool_call_id: tool_call_id.into(),
+                content: content.into(),
+            }),
+        }
+    }
+
+    /// Typed view of this message's content. Falls back to treating the raw value as plain text
+    /// when it wasn't built via [`Message::text`]/[`Message::tool_call`]/[`Message::tool_result`]
+    /// (e.g. legacy callers that still assign `content` directly), so older content keeps working.
+    pub fn content_view(&self) -> MessageContent {
+        if let Ok(typed) = serde_json::from_value::<MessageContent>(self.content.clone()) {
+            return typed;
+        }
+        if let Some(text) = self.content.as_str() {
+            return MessageContent::Text { text: text.to_string() };
+        }
+        MessageContent::Text { text: self.content.to_string() }
+    }
+}
+
+/// A tool call a provider surfaced natively in a turn (Anthropic `tool_use` block, OpenAI
+/// `tool_calls` entry), as opposed to one recovered by scraping a `<tool_use>` tag out of plain
+/// text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRequest {
+    pub id: String,
+    pub tool_name: String,
+    pub tool_input: Value,
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmResponse {
     pub content: String,
+    /// Native tool calls the provider surfaced directly in this turn. Empty for models/backends
+    /// that only ever emit tool use inline as text, which callers should fall back to
+    /// parsing (e.g. `AgenticSystem::check_for_tool_use`).
+    pub tool_calls: Vec<ToolCallRequest>,
     pub message_history: Vec<Message>,
 }
 
@@ -64,6 +134,7 @@ pub trait LlmClient {
         system_message: &str,
         message_history: Option<Vec<Message>>,
         temperature: f32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<LlmResponse>;
 
     async fn send_batch_messages(
@@ -73,6 +144,7 @@ pub trait LlmClient {
         message_history: Option<Vec<Message>>,
         temperature: f32,
         n_responses: u32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<Vec<LlmResponse>>;
 }
 
@@ -100,14 +172,38 @@ impl AnthropicClient {
         message_history
             .iter()
             .map(|msg| {
+                let block = match msg.content_view() {
+                    MessageContent::Text { text } => json!({ "type": "text", "text": text }),
+                    MessageContent::ToolCall { id, tool_name, tool_input } => json!({
+                        "type": "tool_use",
+                        "id": id,
+                        "name": tool_name,
+                        "input": tool_input,
+                    }),
+                    MessageContent::ToolResult { tool_call_id, content } => json!({
+                        "type": "tool_result",
+                        "tool_use_id": tool_call_id,
+                        "content": content,
+                    }),
+                };
                 json!({
                     "role": msg.role,
-                    "content": [
-                        {
-                            "type": "text",
-                            "text": msg.content.as_str().unwrap_or("")
-                        }
-                    ]
+                    "content": [block]
+                })
+            })
+            .collect()
+    }
+
+    /// Anthropic's native tool-calling schema: `{"name", "description", "input_schema"}`, which
+    /// happens to match [`ToolInfo`]'s fields one-to-one.
+    fn format_tools(tools: &[ToolInfo]) -> Vec<Value> {
+        tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "input_schema": tool.input_schema,
                 })
             })
             .collect()
@@ -122,6 +218,7 @@ impl LlmClient for AnthropicClient {
         system_message: &str,
         message_history: Option<Vec<Message>>,
         temperature: f32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<LlmResponse> {
         let msg_history = message_history.unwrap_or_default();
         let mut formatted_history = self.format_message_history(&msg_history);
@@ -137,7 +234,7 @@ impl LlmClient for AnthropicClient {
             ]
         }));
 
-        let request_body = json!({
+        let mut request_body = json!({
             "model": self.model,
             "max_tokens": MAX_OUTPUT_TOKENS,
             "temperature": temperature,
@@ -145,6 +242,12 @@ impl LlmClient for AnthropicClient {
             "messages": formatted_history
         });
 
+        if let Some(tools) = tools {
+            if !tools.is_empty() {
+                request_body["tools"] = json!(Self::format_tools(tools));
+            }
+        }
+
         let operation = || async {
             let response = self
                 .client
@@ -174,24 +277,45 @@ impl LlmClient for AnthropicClient {
 
         let response_json = retry(backoff, operation).await?;
 
-        let content = response_json["content"][0]["text"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?
-            .to_string();
+        let blocks = response_json["content"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?;
+
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        let mut assistant_messages = Vec::new();
+
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("text") => {
+                    let text = block["text"].as_str().unwrap_or("");
+                    content.push_str(text);
+                    assistant_messages.push(Message::text("assistant", text));
+                }
+                Some("tool_use") => {
+                    let id = block["id"].as_str().unwrap_or_default().to_string();
+                    let tool_name = block["name"].as_str().unwrap_or_default().to_string();
+                    let tool_input = block["input"].clone();
+                    assistant_messages.push(Message::tool_call(
+                        "assistant",
+                        id.clone(),
+                        tool_name.clone(),
+                        tool_input.clone(),
+                    ));
+                    tool_calls.push(ToolCallRequest { id, tool_name, tool_input });
+                }
+                _ => {}
+            }
+        }
 
         // Update message history
         let mut new_history = msg_history;
-        new_history.push(Message {
-            role: "user".to_string(),
-            content: json!(message),
-        });
-        new_history.push(Message {
-            role: "assistant".to_string(),
-            content: json!(content.clone()),
-        });
+        new_history.push(Message::text("user", message));
+        new_history.extend(assistant_messages);
 
         Ok(LlmResponse {
             content,
+            tool_calls,
             message_history: new_history,
         })
     }
@@ -203,13 +327,14 @@ impl LlmClient for AnthropicClient {
         message_history: Option<Vec<Message>>,
         temperature: f32,
         n_responses: u32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<Vec<LlmResponse>> {
         // For Anthropic, we need to make multiple individual requests
         let mut responses = Vec::new();
 
         for _ in 0..n_responses {
             let response = self
-                .send_message(message, system_message, message_history.clone(), temperature)
+                .send_message(message, system_message, message_history.clone(), temperature, tools)
                 .await?;
             responses.push(response);
         }
@@ -247,14 +372,79 @@ impl OpenAiClient {
     fn format_message_history(&self, message_history: &[Message]) -> Vec<Value> {
         message_history
             .iter()
-            .map(|msg| {
-                json!({
+            .map(|msg| match msg.content_view() {
+                MessageContent::Text { text } => json!({
                     "role": msg.role,
-                    "content": msg.content.as_str().unwrap_or("")
+                    "content": text,
+                }),
+                MessageContent::ToolCall { id, tool_name, tool_input } => json!({
+                    "role": msg.role,
+                    "content": null,
+                    "tool_calls": [{
+                        "id": id,
+                        "type": "function",
+                        "function": {
+                            "name": tool_name,
+                            "arguments": tool_input.to_string(),
+                        }
+                    }]
+                }),
+                MessageContent::ToolResult { tool_call_id, content } => json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call_id,
+                    "content": content,
+                }),
+            })
+            .collect()
+    }
+
+    /// OpenAI's native tool-calling schema wraps each tool in a `"function"` object.
+    fn format_tools(tools: &[ToolInfo]) -> Vec<Value> {
+        tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.input_schema,
+                    }
                 })
             })
             .collect()
     }
+
+    /// Extract text and any native `tool_calls` out of one `choices[].message` object, returning
+    /// the plain-text content, the structured tool calls, and the `Message`s to append to history.
+    fn parse_response_message(message: &Value) -> (String, Vec<ToolCallRequest>, Vec<Message>) {
+        let content = message["content"].as_str().unwrap_or("").to_string();
+        let mut tool_calls = Vec::new();
+        let mut assistant_messages = Vec::new();
+
+        if !content.is_empty() {
+            assistant_messages.push(Message::text("assistant", &content));
+        }
+
+        if let Some(calls) = message["tool_calls"].as_array() {
+            for call in calls {
+                let id = call["id"].as_str().unwrap_or_default().to_string();
+                let tool_name = call["function"]["name"].as_str().unwrap_or_default().to_string();
+                let arguments = call["function"]["arguments"].as_str().unwrap_or("{}");
+                let tool_input = serde_json::from_str(arguments).unwrap_or(json!({}));
+
+                assistant_messages.push(Message::tool_call(
+                    "assistant",
+                    id.clone(),
+                    tool_name.clone(),
+                    tool_input.clone(),
+                ));
+                tool_calls.push(ToolCallRequest { id, tool_name, tool_input });
+            }
+        }
+
+        (content, tool_calls, assistant_messages)
+    }
 }
 
 #[async_trait]
@@ -265,6 +455,7 @@ impl LlmClient for OpenAiClient {
         system_message: &str,
         message_history: Option<Vec<Message>>,
         temperature: f32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<LlmResponse> {
         let msg_history = message_history.unwrap_or_default();
         let mut formatted_history = self.format_message_history(&msg_history);
@@ -310,6 +501,12 @@ impl LlmClient for OpenAiClient {
             }));
         }
 
+        if let Some(tools) = tools {
+            if !tools.is_empty() {
+                request_body["tools"] = json!(Self::format_tools(tools));
+            }
+        }
+
         let operation = || async {
             let response = self
                 .client
@@ -338,24 +535,17 @@ impl LlmClient for OpenAiClient {
 
         let response_json = retry(backoff, operation).await?;
 
-        let content = response_json["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?
-            .to_string();
+        let (content, tool_calls, assistant_messages) =
+            Self::parse_response_message(&response_json["choices"][0]["message"]);
 
         // Update message history
         let mut new_history = msg_history;
-        new_history.push(Message {
-            role: "user".to_string(),
-            content: json!(message),
-        });
-        new_history.push(Message {
-            role: "assistant".to_string(),
-            content: json!(content.clone()),
-        });
+        new_history.push(Message::text("user", message));
+        new_history.extend(assistant_messages);
 
         Ok(LlmResponse {
             content,
+            tool_calls,
             message_history: new_history,
         })
     }
@@ -367,6 +557,7 @@ impl LlmClient for OpenAiClient {
         message_history: Option<Vec<Message>>,
         temperature: f32,
         n_responses: u32,
+        tools: Option<&[ToolInfo]>,
     ) -> DgmResult<Vec<LlmResponse>> {
         // For certain OpenAI models, we can use the n parameter for batch responses
         if self.model.starts_with("gpt-4o-") && !self.model.starts_with("o1-") && !self.model.starts_with("o3-") {
@@ -383,7 +574,7 @@ impl LlmClient for OpenAiClient {
                 "content": message
             }));
 
-            let request_body = json!({
+            let mut request_body = json!({
                 "model": self.model,
                 "messages": formatted_history,
                 "temperature": temperature,
@@ -393,6 +584,12 @@ impl LlmClient for OpenAiClient {
                 "seed": 0
             });
 
+            if let Some(tools) = tools {
+                if !tools.is_empty() {
+                    request_body["tools"] = json!(Self::format_tools(tools));
+                }
+            }
+
             let operation = || async {
                 let response = self
                     .client
@@ -427,23 +624,16 @@ impl LlmClient for OpenAiClient {
 
             let mut responses = Vec::new();
             for choice in choices {
-                let content = choice["message"]["content"]
-                    .as_str()
-                    .ok_or_else(|| anyhow::anyhow!("Invalid choice format"))?
-                    .to_string();
+                let (content, tool_calls, assistant_messages) =
+                    Self::parse_response_message(&choice["message"]);
 
                 let mut new_history = msg_history.clone();
-                new_history.push(Message {
-                    role: "user".to_string(),
-                    content: json!(message),
-                });
-                new_history.push(Message {
-                    role: "assistant".to_string(),
-                    content: json!(content.clone()),
-                });
+                new_history.push(Message::text("user", message));
+                new_history.extend(assistant_messages);
 
                 responses.push(LlmResponse {
                     content,
+                    tool_calls,
                     message_history: new_history,
                 });
             }
@@ -455,7 +645,7 @@ impl LlmClient for OpenAiClient {
 
             for _ in 0..n_responses {
                 let response = self
-                    .send_message(message, system_message, message_history.clone(), temperature)
+                    .send_message(message, system_message, message_history.clone(), temperature, tools)
                     .await?;
                 responses.push(response);
             }
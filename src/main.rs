@@ -57,6 +57,12 @@ struct Cli {
     /// Baseline to run
     #[arg(long)]
     run_baseline: Option<String>,
+
+    /// Seed for the evolution strategy's parent/entry selection, so a run's self-improvement
+    /// picks can be replayed exactly. Unset draws a seed from entropy; the chosen seed is logged
+    /// so the run can still be reproduced later from the log.
+    #[arg(long)]
+    selection_seed: Option<u64>,
 }
 
 #[tokio::main]
@@ -80,6 +86,7 @@ async fn main() -> DgmResult<()> {
         cli.eval_noise,
         cli.no_full_eval,
         cli.run_baseline,
+        cli.selection_seed,
     )?;
 
     runner.run().await?;
@@ -1,21 +1,513 @@
 use crate::DgmResult;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
 use tokio::fs;
 
-/// Prompt template with placeholders
+pub mod scaffold;
+pub use scaffold::*;
+
+/// One parsed piece of a [`PromptTemplate`]'s body. Built once by [`parse_template`] and cached on
+/// the template (see `PromptTemplate::ast`) so re-rendering the same template doesn't re-parse it.
+#[derive(Debug, Clone)]
+enum TemplateNode {
+    /// Literal text, rendered byte-for-byte with no escaping.
+    Text(String),
+    /// `{{ key }}` or `{{ key | default:"..." }}`.
+    Var { key: String, default: Option<String> },
+    /// `{{#if key}}...{{/if}}`: `body` renders only when `key` is present and non-empty in the
+    /// context.
+    If { key: String, body: Vec<TemplateNode> },
+    /// `{{#each key}}...{{/each}}`: `body` renders once per item in the context's list value for
+    /// `key`, with `this` bound to the current item.
+    Each { key: String, body: Vec<TemplateNode> },
+    /// `{{> template_name}}`: inlines another registered template's rendered output.
+    Include(String),
+    /// Fluent-style `{ $variable -> [case] ... [other] ... }`: `variable` is looked up as a
+    /// number in the context and resolved to a plural category via [`plural_category`], which
+    /// picks the matching arm (falling back to `"other"`).
+    Select { variable: String, cases: Vec<(String, Vec<TemplateNode>)> },
+    /// Fluent-style `{ message_id }`: inlines another registered template's rendered output in
+    /// the same active locale. Semantically identical to [`TemplateNode::Include`]; kept as a
+    /// separate variant only because it's spelled differently (`{ name }` vs `{{> name}}`).
+    MessageRef(String),
+}
+
+/// A value a [`TemplateContext`] can hold: a single string (for `{{ key }}` / `{{#if}}`), a list
+/// of strings (for `{{#each}}`), or an integer (for a Fluent-style `{ $count -> ... }` selector).
+#[derive(Debug, Clone)]
+enum ContextValue {
+    Scalar(String),
+    List(Vec<String>),
+    Number(i64),
+}
+
+/// Render-time context for [`PromptManager::render_template`]. Holds both scalar values and
+/// multi-valued (list) entries, since `{{#each}}` needs the latter and plain `{{ key }}`/`{{#if}}`
+/// only ever need the former.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    values: HashMap<String, ContextValue>,
+}
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a scalar value, usable by `{{ key }}`, `{{#if key}}`, and as a `default:"..."` target.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.values.insert(key.into(), ContextValue::Scalar(value.into()));
+        self
+    }
+
+    /// Set a list value, usable by `{{#each key}}` (and `{{#if key}}`, which treats a non-empty
+    /// list as truthy).
+    pub fn insert_list(&mut self, key: impl Into<String>, values: Vec<String>) -> &mut Self {
+        self.values.insert(key.into(), ContextValue::List(values));
+        self
+    }
+
+    /// Set an integer value, usable by a Fluent-style `{ $key -> [one] ... [other] ... }`
+    /// selector.
+    pub fn insert_number(&mut self, key: impl Into<String>, value: i64) -> &mut Self {
+        self.values.insert(key.into(), ContextValue::Number(value));
+        self
+    }
+
+    fn is_truthy(&self, key: &str) -> bool {
+        match self.values.get(key) {
+            Some(ContextValue::Scalar(s)) => !s.is_empty(),
+            Some(ContextValue::List(items)) => !items.is_empty(),
+            Some(ContextValue::Number(n)) => *n != 0,
+            None => false,
+        }
+    }
+
+    fn scalar(&self, key: &str) -> Option<&str> {
+        match self.values.get(key) {
+            Some(ContextValue::Scalar(s)) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    fn list(&self, key: &str) -> Option<&[String]> {
+        match self.values.get(key) {
+            Some(ContextValue::List(items)) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn number(&self, key: &str) -> Option<i64> {
+        match self.values.get(key) {
+            Some(ContextValue::Number(n)) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Prompt template with an extended mustache-like syntax: `{{ key }}` variables (with an optional
+/// `| default:"..."` fallback), `{{#if key}}...{{/if}}` conditional blocks, `{{#each key}}...{{/each}}`
+/// iteration, `{{> name}}` partial includes of another registered template, and Fluent-style
+/// `{ $var -> [case] ... }` selectors / `{ message_id }` message references for locales other than
+/// `en`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptTemplate {
     pub name: String,
+    /// The `en` (default) body. Always present, even when `locales` carries overrides.
     pub template: String,
+    /// Per-locale overrides of `template`, keyed by BCP-47 tag (e.g. `"fr"`, `"pt-BR"`). A locale
+    /// missing here falls back to `template`.
+    #[serde(default)]
+    pub locales: HashMap<String, String>,
+    /// Historical list of the template's `{key}` placeholders, kept only for backward
+    /// compatibility with existing `load_templates_from_file` JSON documents. The renderer no
+    /// longer consults this: required variables are now discovered directly from `{{ key }}`
+    /// nodes when parsing `template`.
     pub placeholders: Vec<String>,
+    /// Parsed AST per locale, computed lazily on first render and cached for subsequent ones. Not
+    /// serialized; reconstructed from `template`/`locales` on demand.
+    #[serde(skip)]
+    ast_cache: RefCell<HashMap<String, Vec<TemplateNode>>>,
+}
+
+impl PromptTemplate {
+    pub fn new(name: impl Into<String>, template: impl Into<String>, placeholders: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            template: template.into(),
+            locales: HashMap::new(),
+            placeholders,
+            ast_cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Attach a locale-specific override of this template's body, e.g.
+    /// `.with_locale("fr", "...")`. Builder-style, for use alongside `PromptTemplate::new`.
+    pub fn with_locale(mut self, locale: impl Into<String>, body: impl Into<String>) -> Self {
+        self.locales.insert(locale.into(), body.into());
+        self
+    }
+
+    /// The raw template body to use for `locale`, falling back to `template` (the `en` body) when
+    /// `locale` has no override.
+    fn body_for_locale<'a>(&'a self, locale: &str) -> (&'a str, &'a str) {
+        match self.locales.get(locale) {
+            Some(body) => (locale, body.as_str()),
+            None => ("en", self.template.as_str()),
+        }
+    }
+
+    /// Parsed nodes for this template's body in `locale`, parsing and caching on first access.
+    /// Locales without their own override share the cached `"en"` AST.
+    fn ast(&self, locale: &str) -> DgmResult<Vec<TemplateNode>> {
+        let (cache_key, body) = self.body_for_locale(locale);
+        if let Some(cached) = self.ast_cache.borrow().get(cache_key) {
+            return Ok(cached.clone());
+        }
+        let parsed = parse_template(body)
+            .with_context(|| format!("Failed to parse template '{}' (locale '{}')", self.name, cache_key))?;
+        self.ast_cache.borrow_mut().insert(cache_key.to_string(), parsed.clone());
+        Ok(parsed)
+    }
+
+    /// Render this template standalone (not through a [`PromptManager`]) against `context`, in the
+    /// default `en` locale. Supports everything [`PromptManager::render_template`] does except
+    /// `{{> name}}`/`{ message_id }` cross-template references, since a standalone template has no
+    /// registry of other templates to resolve them against. Used by
+    /// [`crate::prompts::scaffold::ScaffoldFile`], whose file bodies are self-contained.
+    pub fn render(&self, context: &TemplateContext) -> DgmResult<String> {
+        let ast = self.ast("en")?;
+        render_nodes_standalone(&ast, context)
+    }
+}
+
+/// Render `nodes` without a [`PromptManager`] registry to resolve `{{> name}}`/`{ message_id }`
+/// against — see [`PromptTemplate::render`].
+fn render_nodes_standalone(nodes: &[TemplateNode], context: &TemplateContext) -> DgmResult<String> {
+    let mut out = String::new();
+
+    for node in nodes {
+        match node {
+            TemplateNode::Text(text) => out.push_str(text),
+            TemplateNode::Var { key, default } => match context.scalar(key).or(default.as_deref()) {
+                Some(value) => out.push_str(value),
+                None => anyhow::bail!("missing context value for '{{{{ {} }}}}' and no default given", key),
+            },
+            TemplateNode::If { key, body } => {
+                if context.is_truthy(key) {
+                    out.push_str(&render_nodes_standalone(body, context)?);
+                }
+            }
+            TemplateNode::Each { key, body } => {
+                if let Some(items) = context.list(key) {
+                    for item in items {
+                        let mut item_context = context.clone();
+                        item_context.insert("this", item.clone());
+                        out.push_str(&render_nodes_standalone(body, &item_context)?);
+                    }
+                }
+            }
+            TemplateNode::Select { variable, cases } => {
+                let n = context
+                    .number(variable)
+                    .ok_or_else(|| anyhow::anyhow!("missing numeric context value for selector '${{{}}}'", variable))?;
+                let category = plural_category("en", n);
+                let chosen = cases
+                    .iter()
+                    .find(|(label, _)| label == category)
+                    .or_else(|| cases.iter().find(|(label, _)| label == "other"))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("selector on '${}' has no arm for category '{}' and no 'other' fallback", variable, category)
+                    })?;
+                out.push_str(&render_nodes_standalone(&chosen.1, context)?);
+            }
+            TemplateNode::Include(name) | TemplateNode::MessageRef(name) => {
+                anyhow::bail!(
+                    "template references '{{{{> {name}}}}}' (or '{{ {name} }}'), but standalone rendering has no template registry to resolve it against"
+                );
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Parse a template body into a flat-at-top-level list of [`TemplateNode`]s, recursing into
+/// `{{#if}}`/`{{#each}}` bodies as they're encountered.
+fn parse_template(template: &str) -> DgmResult<Vec<TemplateNode>> {
+    let (nodes, _) = parse_nodes(template, 0, None)?;
+    Ok(nodes)
+}
+
+/// Parse `src[pos..]` until either the string ends (`closing` is `None`, the top-level call) or a
+/// matching `{{/closing}}` directive is found, returning the parsed nodes and the byte offset just
+/// past whatever ended the scan. A lone `{` (not immediately followed by a second `{`) starts a
+/// Fluent-style directive instead (see [`parse_fluent_directive`]), matched by brace depth rather
+/// than a literal `"}"` search since a `{ $var -> ... }` selector's arms may themselves contain
+/// nested `{ ... }` references.
+fn parse_nodes(src: &str, mut pos: usize, closing: Option<&str>) -> DgmResult<(Vec<TemplateNode>, usize)> {
+    let mut nodes = Vec::new();
+
+    loop {
+        let Some(rel_start) = src[pos..].find('{') else {
+            if let Some(tag) = closing {
+                anyhow::bail!("unterminated {{{{#{tag}}}}} block: missing matching {{{{/{tag}}}}}");
+            }
+            if pos < src.len() {
+                nodes.push(TemplateNode::Text(src[pos..].to_string()));
+            }
+            return Ok((nodes, src.len()));
+        };
+
+        let tag_start = pos + rel_start;
+        let is_mustache = src[tag_start..].starts_with("{{");
+
+        if !is_mustache {
+            let brace_end = find_matching_brace(src, tag_start)?;
+            if tag_start > pos {
+                nodes.push(TemplateNode::Text(src[pos..tag_start].to_string()));
+            }
+            let inner = &src[tag_start + 1..brace_end];
+            nodes.push(parse_fluent_directive(inner)?);
+            pos = brace_end + 1;
+            continue;
+        }
+
+        if tag_start > pos {
+            nodes.push(TemplateNode::Text(src[pos..tag_start].to_string()));
+        }
+
+        let after_open = tag_start + 2;
+        let rel_end = src[after_open..]
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("unterminated directive: missing '}}}}' after byte {after_open}"))?;
+        let tag_end = after_open + rel_end;
+        let directive = src[after_open..tag_end].trim();
+        let next_pos = tag_end + 2;
+
+        if let Some(tag) = closing {
+            if directive == format!("/{tag}") {
+                return Ok((nodes, next_pos));
+            }
+        }
+
+        if let Some(key) = directive.strip_prefix("#if") {
+            let key = key.trim().to_string();
+            let (body, after) = parse_nodes(src, next_pos, Some("if"))?;
+            nodes.push(TemplateNode::If { key, body });
+            pos = after;
+        } else if let Some(key) = directive.strip_prefix("#each") {
+            let key = key.trim().to_string();
+            let (body, after) = parse_nodes(src, next_pos, Some("each"))?;
+            nodes.push(TemplateNode::Each { key, body });
+            pos = after;
+        } else if let Some(name) = directive.strip_prefix('>') {
+            nodes.push(TemplateNode::Include(name.trim().to_string()));
+            pos = next_pos;
+        } else if directive.starts_with('/') {
+            anyhow::bail!("unexpected closing directive '{{{{{directive}}}}}' with nothing open");
+        } else {
+            let (key, default) = parse_var(directive)?;
+            nodes.push(TemplateNode::Var { key, default });
+            pos = next_pos;
+        }
+    }
+}
+
+/// Find the `}` matching the `{` at `src[open_pos]`, counting brace depth so a nested `{ ... }`
+/// (e.g. inside a selector arm's body) doesn't terminate the outer directive early.
+fn find_matching_brace(src: &str, open_pos: usize) -> DgmResult<usize> {
+    let mut depth = 0i32;
+    for (offset, ch) in src[open_pos..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(open_pos + offset);
+                }
+            }
+            _ => {}
+        }
+    }
+    anyhow::bail!("unterminated '{{' directive starting at byte {open_pos}: missing matching '}}'");
+}
+
+/// Parse the inside of a Fluent-style `{ ... }` directive (the brace-matched `inner` text, not
+/// including the outer braces) into one [`TemplateNode`]:
+/// - `$var -> [case] ... [other] ...` becomes a [`TemplateNode::Select`].
+/// - `$var` alone becomes the same [`TemplateNode::Var`] a `{{ var }}` would.
+/// - anything else is taken as a bare message id and becomes a [`TemplateNode::MessageRef`].
+fn parse_fluent_directive(inner: &str) -> DgmResult<TemplateNode> {
+    let trimmed = inner.trim();
+
+    if let Some(arrow_pos) = trimmed.find("->") {
+        let variable = trimmed[..arrow_pos]
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| anyhow::anyhow!("selector variable '{}' must start with '$'", trimmed[..arrow_pos].trim()))?
+            .to_string();
+        let cases = parse_selector_arms(trimmed[arrow_pos + 2..].trim())?;
+        return Ok(TemplateNode::Select { variable, cases });
+    }
+
+    if let Some(var_name) = trimmed.strip_prefix('$') {
+        return Ok(TemplateNode::Var { key: var_name.trim().to_string(), default: None });
+    }
+
+    Ok(TemplateNode::MessageRef(trimmed.to_string()))
+}
+
+/// Parse a selector's `[case] body [case] body ...` arms, splitting on top-level (brace-depth-0)
+/// `[` so an arm's own body (which may itself contain nested `{ ... }` references) isn't mistaken
+/// for another arm boundary.
+fn parse_selector_arms(src: &str) -> DgmResult<Vec<(String, Vec<TemplateNode>)>> {
+    let mut depth = 0i32;
+    let mut arm_starts = Vec::new();
+    for (offset, ch) in src.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '[' if depth == 0 => arm_starts.push(offset),
+            _ => {}
+        }
+    }
+    if arm_starts.is_empty() {
+        anyhow::bail!("selector has no '[case]' arms");
+    }
+
+    let mut arms = Vec::new();
+    for (index, &start) in arm_starts.iter().enumerate() {
+        let close = src[start..]
+            .find(']')
+            .map(|rel| start + rel)
+            .ok_or_else(|| anyhow::anyhow!("selector arm label starting at byte {start} is missing ']'"))?;
+        let label = src[start + 1..close].trim().to_string();
+        let body_end = arm_starts.get(index + 1).copied().unwrap_or(src.len());
+        let (body, _) = parse_nodes(&src[close + 1..body_end], 0, None)?;
+        arms.push((label, body));
+    }
+
+    Ok(arms)
+}
+
+/// Resolve which plural category `n` falls into for `locale`, Fluent-selector-style. Only
+/// English's simple cardinal rule (`1` is `"one"`, everything else is `"other"`) is implemented —
+/// real CLDR plural rules vary per locale (e.g. Arabic has six categories) and this repo doesn't
+/// vendor the CLDR data set, so unrecognized locales fall back to the English rule.
+fn plural_category(_locale: &str, n: i64) -> &'static str {
+    if n == 1 {
+        "one"
+    } else {
+        "other"
+    }
+}
+
+/// Parse the inside of a plain `{{ ... }}` variable directive: either just a key, or
+/// `key | default:"fallback text"`.
+fn parse_var(directive: &str) -> DgmResult<(String, Option<String>)> {
+    let Some((key_part, filter_part)) = directive.split_once('|') else {
+        return Ok((directive.trim().to_string(), None));
+    };
+
+    let key = key_part.trim().to_string();
+    let filter_part = filter_part.trim();
+    let Some(default_literal) = filter_part.strip_prefix("default:") else {
+        anyhow::bail!("unsupported template filter '{filter_part}' (only 'default:\"...\"' is supported)");
+    };
+
+    let default_literal = default_literal.trim();
+    let unquoted = default_literal
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(default_literal);
+
+    Ok((key, Some(unquoted.to_string())))
+}
+
+/// Render one [`crate::tools::schema::ToolSchema`] as a documented Python function stub for
+/// [`PromptManager::get_code_action_prompt`]: a `def <name>(...) -> str:` signature with one
+/// parameter per `input_schema` property (required ones first, optional ones defaulting to
+/// `None`), plus a docstring listing each parameter's type and description.
+fn build_tool_stub(schema: &crate::tools::schema::ToolSchema) -> String {
+    let properties = schema.input_schema.get("properties").and_then(|v| v.as_object());
+    let required: Vec<&str> = schema
+        .input_schema
+        .get("required")
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut params = Vec::new();
+    let mut optional_params = Vec::new();
+    let mut doc_lines = Vec::new();
+
+    if let Some(properties) = properties {
+        for (name, property_schema) in properties {
+            let json_type = property_schema.get("type").and_then(|v| v.as_str()).unwrap_or("string");
+            let description = property_schema.get("description").and_then(|v| v.as_str()).unwrap_or("");
+            let py_type = json_schema_type_to_python(json_type);
+            let is_required = required.contains(&name.as_str());
+
+            if is_required {
+                params.push(format!("{name}: {py_type}"));
+                doc_lines.push(format!("        {name} ({py_type}): {description}"));
+            } else {
+                optional_params.push(format!("{name}: {py_type} = None"));
+                doc_lines.push(format!("        {name} ({py_type}, optional): {description}"));
+            }
+        }
+    }
+    // Python rejects a parameter without a default after one with a default.
+    params.extend(optional_params);
+
+    let doc_body = if doc_lines.is_empty() {
+        String::new()
+    } else {
+        format!("\n\n    Args:\n{}", doc_lines.join("\n"))
+    };
+
+    format!(
+        "def {name}({params}) -> str:\n    \"\"\"{description}{doc_body}\n    \"\"\"",
+        name = schema.name,
+        params = params.join(", "),
+        description = schema.description,
+    )
+}
+
+/// Map a JSON Schema primitive type to the closest Python type annotation.
+fn json_schema_type_to_python(json_type: &str) -> &'static str {
+    match json_type {
+        "integer" => "int",
+        "number" => "float",
+        "boolean" => "bool",
+        "array" => "list",
+        "object" => "dict",
+        _ => "str",
+    }
+}
+
+/// Which protocol a model without built-in tool calling should use to invoke tools, so a caller
+/// can pick per model capability instead of the crate hardcoding one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToolUseStrategy {
+    /// One `<tool_use>` JSON object per turn (see [`PromptManager::get_tooluse_prompt`]).
+    #[default]
+    Json,
+    /// A single fenced Python snippet that calls the tools as ordinary functions (see
+    /// [`PromptManager::get_code_action_prompt`]).
+    CodeAction,
 }
 
 /// Prompt manager for handling LLM prompts and templates
 pub struct PromptManager {
     templates: HashMap<String, PromptTemplate>,
+    /// BCP-47 tag `render_template`/`get_tooluse_prompt` et al. render against when no explicit
+    /// locale is given. Defaults to `"en"`; see `set_locale` and `render_template_in_locale`.
+    active_locale: String,
 }
 
 impl PromptManager {
@@ -23,6 +515,7 @@ impl PromptManager {
     pub fn new() -> Self {
         let mut manager = Self {
             templates: HashMap::new(),
+            active_locale: "en".to_string(),
         };
 
         // Initialize built-in templates
@@ -30,12 +523,23 @@ impl PromptManager {
         manager
     }
 
+    /// Set the locale subsequent `render_template` calls render against. Templates (or locale
+    /// message refs) without a body for `locale` fall back to their `en` one.
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.active_locale = locale.into();
+    }
+
+    /// The locale `render_template` currently renders against.
+    pub fn active_locale(&self) -> &str {
+        &self.active_locale
+    }
+
     /// Initialize built-in prompt templates
     fn init_builtin_templates(&mut self) {
         // Coding agent summary template
-        let coding_agent_summary = PromptTemplate {
-            name: "coding_agent_summary".to_string(),
-            template: r#"# Coding Agent Summary
+        let coding_agent_summary = PromptTemplate::new(
+            "coding_agent_summary",
+            r#"# Coding Agent Summary
 
 - **Main File**: `coding_agent.py`
   - Primary Class: `AgenticSystem`
@@ -57,14 +561,14 @@ impl PromptManager {
   - Verify the implementation details of helper functions prior to usage to ensure proper integration and expected behavior.
   - Do not install additional packages or dependencies directly. Update `requirements.txt` if new dependencies are required and install them using `pip install -r requirements.txt`.
 
-"#.to_string(),
-            placeholders: vec![],
-        };
+"#,
+            vec![],
+        );
 
         // Polyglot coding agent summary template
-        let coding_agent_summary_polyglot = PromptTemplate {
-            name: "coding_agent_summary_polyglot".to_string(),
-            template: r#"# Coding Agent Summary
+        let coding_agent_summary_polyglot = PromptTemplate::new(
+            "coding_agent_summary_polyglot",
+            r#"# Coding Agent Summary
 
 - **Main File**: `coding_agent.py`
   - Primary Class: `AgenticSystem`
@@ -96,57 +600,86 @@ Pay special attention to making sure that "required" and "type" are always at th
 Make sure that every property, no matter how short, has a type and description correctly nested inside it.
 Other arguments than you have seen are not permitted. For example, in "edit_line_ranges" with "type": "array", arguments like "minItems" and "maxItems" are not permitted.
 
-"#.to_string(),
-            placeholders: vec![],
-        };
+"#,
+            vec![],
+        );
 
-        // Diagnostic system message template
-        let diagnose_system_message = PromptTemplate {
-            name: "diagnose_system_message".to_string(),
-            template: r#"Here is the implementation of the coding agent.
+        // Diagnostic system message template, composed into the SWE/Polyglot variants below via
+        // `{{> ...}}` instead of each caller `format!`-ing the pieces together.
+        let diagnose_system_message = PromptTemplate::new(
+            "diagnose_system_message",
+            r#"Here is the implementation of the coding agent.
 
 # Coding Agent Implementation
 ----- Coding Agent Implementation Start -----
-{code}
+{{ code }}
 ----- Coding Agent Implementation End -----
 
-Your task is to identify ONE detailed plan that would improve the agent's coding ability. The improvement should not be specific to any particular GitHub issue or repository."#.to_string(),
-            placeholders: vec!["code".to_string()],
-        };
+Your task is to identify ONE detailed plan that would improve the agent's coding ability. The improvement should not be specific to any particular GitHub issue or repository."#,
+            vec!["code".to_string()],
+        );
 
-        // Diagnostic prompt template
-        let diagnose_prompt = PromptTemplate {
-            name: "diagnose_prompt".to_string(),
-            template: r#"
+        let diagnose_system_message_swe = PromptTemplate::new(
+            "diagnose_system_message_swe",
+            "{{> coding_agent_summary}}\n{{> diagnose_system_message}}",
+            vec!["code".to_string()],
+        );
+
+        let diagnose_system_message_polyglot = PromptTemplate::new(
+            "diagnose_system_message_polyglot",
+            "{{> coding_agent_summary_polyglot}}\n{{> diagnose_system_message}}",
+            vec!["code".to_string()],
+        );
+
+        // Diagnostic prompt template. Each section is wrapped in `{{#if}}` so callers that don't
+        // have a value for it (e.g. the empty-patches flow has no `predicted_patch`/`test_patch`)
+        // can simply omit it from the context instead of needing a separate near-duplicate
+        // template.
+        let diagnose_prompt = PromptTemplate::new(
+            "diagnose_prompt",
+            r#"{{#if empty_patch_note}}
+{{ empty_patch_note }}
+
+{{/if}}
+{{#if md_log}}
 # Agent Running Log
 ----- Agent Running Log Start -----
-{md_log}
+{{ md_log }}
 ----- Agent Running Log End -----
 
+{{/if}}
+{{#if github_issue}}
 # GitHub Issue
 The GitHub issue that the agent is trying to solve.
 ----- GitHub Issue Start -----
-{github_issue}
+{{ github_issue }}
 ----- GitHub Issue End -----
 
+{{/if}}
+{{#if predicted_patch}}
 # Predicted Patch
 The agent's predicted patch to solve the issue.
 ----- Predicted Patch Start -----
-{predicted_patch}
+{{ predicted_patch }}
 ----- Predicted Patch End -----
 
+{{/if}}
+{{#if test_patch}}
 # Private Test Patch
 SWE-bench's official private tests to detect whether the issue is solved. This is not available to the agent during evaluation. The agent should try to implement its own tests.
 ----- Private Test Patch Start -----
-{test_patch}
+{{ test_patch }}
 ----- Private Test Patch End -----
 
+{{/if}}
+{{#if eval_log}}
 # Issue Test Results
 The test results from SWE-bench using the above official private tests.
 ----- Issue Test Results Start -----
-{eval_log}
+{{ eval_log }}
 ----- Issue Test Results End -----
 
+{{/if}}
 Respond precisely in the following format including the JSON start and end markers:
 
 ```json
@@ -160,20 +693,20 @@ In <JSON>, provide a JSON response with the following fields:
 - "implementation_suggestion": Referring to the coding agent's summary and implementation, think critically about what feature or tool could be added or improved to best implement the proposed improvement. If the proposed feature can be implemented by modifying the existing tools, describe the modifications needed, instead of suggesting a new tool.
 - "problem_description": Phrase the improvement proposal and implementation suggestion as a GitHub issue description. It should clearly describe the feature so that a software engineer viewing the issue and the repository can implement it.
 
-Your response will be automatically parsed, so ensure that the string response is precisely in the correct format. Do NOT include the `<JSON>` tag in your output."#.to_string(),
-            placeholders: vec![
+Your response will be automatically parsed, so ensure that the string response is precisely in the correct format. Do NOT include the `<JSON>` tag in your output."#,
+            vec![
                 "md_log".to_string(),
                 "github_issue".to_string(),
                 "predicted_patch".to_string(),
                 "test_patch".to_string(),
                 "eval_log".to_string(),
             ],
-        };
+        );
 
         // Empty patches diagnostic prompt
-        let diagnose_prompt_emptypatches = PromptTemplate {
-            name: "diagnose_prompt_emptypatches".to_string(),
-            template: r#"There are some empty patches when attempting to solve GitHub issues. Since the coding agent is stochastic, it may not always produce a patch. Handle cases where the coding agent fails to generate a patch or generates one that only modifies the test cases without editing the primary source code. For example, the simplest solution is to ask the agent to try again.
+        let diagnose_prompt_emptypatches = PromptTemplate::new(
+            "diagnose_prompt_emptypatches",
+            r#"There are some empty patches when attempting to solve GitHub issues. Since the coding agent is stochastic, it may not always produce a patch. Handle cases where the coding agent fails to generate a patch or generates one that only modifies the test cases without editing the primary source code. For example, the simplest solution is to ask the agent to try again.
 
 Respond precisely in the following format including the JSON start and end markers:
 
@@ -187,15 +720,37 @@ In <JSON>, provide a JSON response with the following fields:
 - "implementation_suggestion": Referring to the coding agent's summary and implementation, think critically about what feature could be added or improved to best implement the proposed improvement.
 - "problem_description": Phrase the improvement proposal and implementation suggestion as a GitHub issue description. It should clearly describe the feature so that a software engineer viewing the issue and the repository can implement it.
 
-Your response will be automatically parsed, so ensure that the string response is precisely in the correct format. Do NOT include the `<JSON>` tag in your output."#.to_string(),
-            placeholders: vec![],
-        };
+Your response will be automatically parsed, so ensure that the string response is precisely in the correct format. Do NOT include the `<JSON>` tag in your output."#,
+            vec![],
+        );
 
-        self.templates.insert("coding_agent_summary".to_string(), coding_agent_summary);
-        self.templates.insert("coding_agent_summary_polyglot".to_string(), coding_agent_summary_polyglot);
-        self.templates.insert("diagnose_system_message".to_string(), diagnose_system_message);
-        self.templates.insert("diagnose_prompt".to_string(), diagnose_prompt);
-        self.templates.insert("diagnose_prompt_emptypatches".to_string(), diagnose_prompt_emptypatches);
+        // Problem description templates, composed from the coding agent summary the same way the
+        // diagnose system messages are.
+        let problem_description_prompt = PromptTemplate::new(
+            "problem_description_prompt",
+            "{{> coding_agent_summary}}\n# To Implement\n\n{{ implementation_suggestion }}\n\n{{ problem_description }}",
+            vec!["implementation_suggestion".to_string(), "problem_description".to_string()],
+        );
+
+        let problem_description_prompt_polyglot = PromptTemplate::new(
+            "problem_description_prompt_polyglot",
+            "{{> coding_agent_summary_polyglot}}\n# To Implement\n\n{{ implementation_suggestion }}\n\n{{ problem_description }}",
+            vec!["implementation_suggestion".to_string(), "problem_description".to_string()],
+        );
+
+        for template in [
+            coding_agent_summary,
+            coding_agent_summary_polyglot,
+            diagnose_system_message,
+            diagnose_system_message_swe,
+            diagnose_system_message_polyglot,
+            diagnose_prompt,
+            diagnose_prompt_emptypatches,
+            problem_description_prompt,
+            problem_description_prompt_polyglot,
+        ] {
+            self.templates.insert(template.name.clone(), template);
+        }
     }
 
     /// Get a template by name
@@ -203,24 +758,82 @@ Your response will be automatically parsed, so ensure that the string response i
         self.templates.get(name)
     }
 
-    /// Render a template with the given context
-    pub fn render_template(&self, name: &str, context: &HashMap<String, String>) -> DgmResult<String> {
-        let template = self.templates.get(name)
-            .ok_or_else(|| anyhow::anyhow!("Template '{}' not found", name))?;
+    /// Render a template against `context` in the manager's `active_locale`, resolving
+    /// `{{#if}}`/`{{#each}}`/`{{> ...}}`/`{{ key }}` directives and their Fluent-style
+    /// `{ $var -> ... }`/`{ message_id }` counterparts.
+    pub fn render_template(&self, name: &str, context: &TemplateContext) -> DgmResult<String> {
+        self.render_template_in_locale(name, context, &self.active_locale)
+    }
 
-        let mut rendered = template.template.clone();
+    /// Like [`Self::render_template`], but rendering against `locale` instead of the manager's
+    /// `active_locale` — useful for a caller rendering several locales from one manager without
+    /// repeatedly flipping `set_locale`.
+    pub fn render_template_in_locale(&self, name: &str, context: &TemplateContext, locale: &str) -> DgmResult<String> {
+        let template = self.templates.get(name).ok_or_else(|| anyhow::anyhow!("Template '{}' not found", name))?;
+        let ast = template.ast(locale)?;
+        let mut visiting = vec![name.to_string()];
+        self.render_nodes(&ast, context, &mut visiting, locale)
+    }
 
-        // Replace placeholders with context values
-        for placeholder in &template.placeholders {
-            let placeholder_key = format!("{{{}}}", placeholder);
-            if let Some(value) = context.get(placeholder) {
-                rendered = rendered.replace(&placeholder_key, value);
-            } else {
-                return Err(anyhow::anyhow!("Missing context value for placeholder '{}'", placeholder).into());
+    fn render_nodes(
+        &self,
+        nodes: &[TemplateNode],
+        context: &TemplateContext,
+        visiting: &mut Vec<String>,
+        locale: &str,
+    ) -> DgmResult<String> {
+        let mut out = String::new();
+
+        for node in nodes {
+            match node {
+                TemplateNode::Text(text) => out.push_str(text),
+                TemplateNode::Var { key, default } => match context.scalar(key).or(default.as_deref()) {
+                    Some(value) => out.push_str(value),
+                    None => anyhow::bail!("missing context value for '{{{{ {} }}}}' and no default given", key),
+                },
+                TemplateNode::If { key, body } => {
+                    if context.is_truthy(key) {
+                        out.push_str(&self.render_nodes(body, context, visiting, locale)?);
+                    }
+                }
+                TemplateNode::Each { key, body } => {
+                    if let Some(items) = context.list(key) {
+                        for item in items {
+                            let mut item_context = context.clone();
+                            item_context.insert("this", item.clone());
+                            out.push_str(&self.render_nodes(body, &item_context, visiting, locale)?);
+                        }
+                    }
+                }
+                TemplateNode::Include(name) | TemplateNode::MessageRef(name) => {
+                    if visiting.iter().any(|visited| visited == name) {
+                        anyhow::bail!("cyclic template include detected: {} -> {}", visiting.join(" -> "), name);
+                    }
+                    let included = self.templates.get(name).ok_or_else(|| anyhow::anyhow!("included template '{}' not found", name))?;
+                    let included_ast = included.ast(locale)?;
+                    visiting.push(name.clone());
+                    let rendered = self.render_nodes(&included_ast, context, visiting, locale);
+                    visiting.pop();
+                    out.push_str(&rendered?);
+                }
+                TemplateNode::Select { variable, cases } => {
+                    let n = context
+                        .number(variable)
+                        .ok_or_else(|| anyhow::anyhow!("missing numeric context value for selector '${{{}}}'", variable))?;
+                    let category = plural_category(locale, n);
+                    let chosen = cases
+                        .iter()
+                        .find(|(label, _)| label == category)
+                        .or_else(|| cases.iter().find(|(label, _)| label == "other"))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("selector on '${}' has no arm for category '{}' and no 'other' fallback", variable, category)
+                        })?;
+                    out.push_str(&self.render_nodes(&chosen.1, context, visiting, locale)?);
+                }
             }
         }
 
-        Ok(rendered)
+        Ok(out)
     }
 
     /// Get self-improvement prompt for SWE-bench
@@ -233,21 +846,16 @@ Your response will be automatically parsed, so ensure that the string response i
         eval_log: &str,
         code: &str,
     ) -> DgmResult<(String, String)> {
-        let mut context = HashMap::new();
-        context.insert("code".to_string(), code.to_string());
-
-        let system_message = self.render_template("diagnose_system_message", &context)?;
-        let coding_summary = self.get_template("coding_agent_summary")
-            .ok_or_else(|| anyhow::anyhow!("coding_agent_summary template not found"))?;
+        let mut system_context = TemplateContext::new();
+        system_context.insert("code", code);
+        let full_system_message = self.render_template("diagnose_system_message_swe", &system_context)?;
 
-        let full_system_message = format!("{}\n{}", coding_summary.template, system_message);
-
-        let mut prompt_context = HashMap::new();
-        prompt_context.insert("md_log".to_string(), md_log.to_string());
-        prompt_context.insert("github_issue".to_string(), github_issue.to_string());
-        prompt_context.insert("predicted_patch".to_string(), predicted_patch.to_string());
-        prompt_context.insert("test_patch".to_string(), test_patch.to_string());
-        prompt_context.insert("eval_log".to_string(), eval_log.to_string());
+        let mut prompt_context = TemplateContext::new();
+        prompt_context.insert("md_log", md_log);
+        prompt_context.insert("github_issue", github_issue);
+        prompt_context.insert("predicted_patch", predicted_patch);
+        prompt_context.insert("test_patch", test_patch);
+        prompt_context.insert("eval_log", eval_log);
 
         let user_prompt = format!(
             "Here is the log for the coding agent trying to solve the GitHub issues but failed.\n{}",
@@ -267,21 +875,16 @@ Your response will be automatically parsed, so ensure that the string response i
         eval_log: &str,
         code: &str,
     ) -> DgmResult<(String, String)> {
-        let mut context = HashMap::new();
-        context.insert("code".to_string(), code.to_string());
-
-        let system_message = self.render_template("diagnose_system_message", &context)?;
-        let coding_summary = self.get_template("coding_agent_summary_polyglot")
-            .ok_or_else(|| anyhow::anyhow!("coding_agent_summary_polyglot template not found"))?;
-
-        let full_system_message = format!("{}\n{}", coding_summary.template, system_message);
+        let mut system_context = TemplateContext::new();
+        system_context.insert("code", code);
+        let full_system_message = self.render_template("diagnose_system_message_polyglot", &system_context)?;
 
-        let mut prompt_context = HashMap::new();
-        prompt_context.insert("md_log".to_string(), md_log.to_string());
-        prompt_context.insert("github_issue".to_string(), github_issue.to_string());
-        prompt_context.insert("predicted_patch".to_string(), predicted_patch.to_string());
-        prompt_context.insert("test_patch".to_string(), test_patch.to_string());
-        prompt_context.insert("eval_log".to_string(), eval_log.to_string());
+        let mut prompt_context = TemplateContext::new();
+        prompt_context.insert("md_log", md_log);
+        prompt_context.insert("github_issue", github_issue);
+        prompt_context.insert("predicted_patch", predicted_patch);
+        prompt_context.insert("test_patch", test_patch);
+        prompt_context.insert("eval_log", eval_log);
 
         let user_prompt = format!(
             "Here is the log for the coding agent trying to solve a programming task. A task is in one programming language, but the coding agent needs to deal with different languages including C++, Go, Java, JavaScript, Python, and Rust.\n{}",
@@ -291,25 +894,17 @@ Your response will be automatically parsed, so ensure that the string response i
         Ok((full_system_message, user_prompt))
     }
 
-    /// Get empty patches diagnostic prompt
+    /// Get empty patches diagnostic prompt. Reuses `diagnose_system_message_{swe,polyglot}` the
+    /// same way the non-empty-patch flows do, now that the common header is a declarative
+    /// `{{> coding_agent_summary}}` include rather than something only `format!` could compose.
     pub fn get_empty_patches_prompt(&self, code: &str, is_polyglot: bool) -> DgmResult<(String, String)> {
-        let mut context = HashMap::new();
-        context.insert("code".to_string(), code.to_string());
-
-        let system_message = self.render_template("diagnose_system_message", &context)?;
-        let template_name = if is_polyglot {
-            "coding_agent_summary_polyglot"
-        } else {
-            "coding_agent_summary"
-        };
+        let mut context = TemplateContext::new();
+        context.insert("code", code);
 
-        let coding_summary = self.get_template(template_name)
-            .ok_or_else(|| anyhow::anyhow!("{} template not found", template_name))?;
+        let template_name = if is_polyglot { "diagnose_system_message_polyglot" } else { "diagnose_system_message_swe" };
+        let full_system_message = self.render_template(template_name, &context)?;
 
-        let full_system_message = format!("{}\n{}", coding_summary.template, system_message);
-        let user_prompt = self.get_template("diagnose_prompt_emptypatches")
-            .ok_or_else(|| anyhow::anyhow!("diagnose_prompt_emptypatches template not found"))?
-            .template.clone();
+        let user_prompt = self.render_template("diagnose_prompt_emptypatches", &TemplateContext::new())?;
 
         Ok((full_system_message, user_prompt))
     }
@@ -319,7 +914,8 @@ Your response will be automatically parsed, so ensure that the string response i
         self.templates.insert(template.name.clone(), template);
     }
 
-    /// Load templates from a JSON file
+    /// Load templates from a JSON file, including each template's `locales` map if present — this
+    /// is how translated bodies reach the manager without forking any Rust, per [`PromptTemplate`].
     pub async fn load_templates_from_file(&mut self, path: &Path) -> DgmResult<()> {
         let content = fs::read_to_string(path).await
             .with_context(|| format!("Failed to read templates file: {:?}", path))?;
@@ -346,8 +942,40 @@ Your response will be automatically parsed, so ensure that the string response i
         Ok(())
     }
 
-    /// Get tool use prompt for LLMs without built-in tool calling
+    /// Get tool use prompt for LLMs without built-in tool calling. Prefers surfacing a
+    /// constrained grammar over the tools' validated schemas (see
+    /// [`crate::tools::schema::build_tool_grammar`]) so a model backend that can decode against a
+    /// grammar is steered away from the `required`/`type`-nesting mistakes
+    /// `coding_agent_summary_polyglot` warns about by hand; falls back to the previous ad-hoc
+    /// `<tool_use>` text format when any tool's schema fails validation, since a constrained
+    /// grammar built from a malformed schema would just reproduce the mistake.
     pub async fn get_tooluse_prompt(&self, tools_dir: &Path) -> DgmResult<String> {
+        let (schemas, issues) = crate::tools::schema::load_tool_schemas(tools_dir).await?;
+
+        if issues.is_empty() && !schemas.is_empty() {
+            let grammar = crate::tools::schema::build_tool_grammar(&schemas);
+            return Ok(format!(
+                r#"Here are the available tools, as a JSON Schema grammar your response must conform to:
+```json
+{grammar}
+```
+
+Respond with a single JSON object matching one of the `oneOf` alternatives above, wrapped like this:
+```
+<tool_use>
+{{
+    'tool_name': ...,
+    'tool_input': ...
+}}
+</tool_use>
+```"#
+            ));
+        }
+
+        for issue in &issues {
+            tracing::warn!("Tool schema validation issue, falling back to the unconstrained tool-use prompt: {}", issue);
+        }
+
         let mut tool_contents = Vec::new();
 
         let mut entries = fs::read_dir(tools_dir).await
@@ -383,6 +1011,50 @@ Use the available tools in this format:
         Ok(tooluse_prompt)
     }
 
+    /// Dispatch to [`Self::get_tooluse_prompt`] or [`Self::get_code_action_prompt`] per `strategy`,
+    /// so a caller can pick the tool-use protocol that best suits a given model without needing to
+    /// know either prompt's shape.
+    pub async fn get_agent_action_prompt(&self, tools_dir: &Path, strategy: ToolUseStrategy) -> DgmResult<String> {
+        match strategy {
+            ToolUseStrategy::Json => self.get_tooluse_prompt(tools_dir).await,
+            ToolUseStrategy::CodeAction => self.get_code_action_prompt(tools_dir).await,
+        }
+    }
+
+    /// Get the "code as action" tool-use prompt: instead of one `<tool_use>` JSON object per turn,
+    /// the model writes a single Python snippet that calls the available tools as ordinary
+    /// functions — composing their outputs, looping, branching — which the harness then executes.
+    /// Tool stub signatures are derived from the same validated schemas
+    /// [`Self::get_tooluse_prompt`]'s grammar path uses, via [`crate::tools::schema::load_tool_schemas`].
+    /// Pair this with [`crate::utils::extract_code_between_markers`] to pull the snippet back out
+    /// of the model's response, and [`crate::utils::validate_code_action`] to check it before
+    /// running it.
+    pub async fn get_code_action_prompt(&self, tools_dir: &Path) -> DgmResult<String> {
+        let (schemas, issues) = crate::tools::schema::load_tool_schemas(tools_dir).await?;
+        for issue in &issues {
+            tracing::warn!("Tool schema validation issue while building the code-action prompt: {}", issue);
+        }
+
+        let stubs: Vec<String> = schemas.iter().map(build_tool_stub).collect();
+        let tools_available = stubs.join("\n\n");
+
+        Ok(format!(
+            r#"Here are the available tools, as Python function stubs:
+```python
+{tools_available}
+```
+
+Respond with a single fenced Python code block that calls these functions as ordinary Python
+(composing their outputs, looping, branching — whatever the task needs) and ends by assigning its
+final answer to a variable named `result`:
+```python
+<your code here>
+result = ...
+```
+Do not use 'while True' loops; they can cause the agent to get stuck and not respond."#
+        ))
+    }
+
     /// Get problem description prompt for self-improvement
     pub fn get_problem_description_prompt(
         &self,
@@ -390,22 +1062,12 @@ Use the available tools in this format:
         problem_description: &str,
         is_polyglot: bool,
     ) -> DgmResult<String> {
-        let template_name = if is_polyglot {
-            "coding_agent_summary_polyglot"
-        } else {
-            "coding_agent_summary"
-        };
-
-        let coding_summary = self.get_template(template_name)
-            .ok_or_else(|| anyhow::anyhow!("{} template not found", template_name))?;
-
-        let problem_template = format!(
-            "# To Implement\n\n{}\n\n{}",
-            implementation_suggestion,
-            problem_description
-        );
+        let mut context = TemplateContext::new();
+        context.insert("implementation_suggestion", implementation_suggestion);
+        context.insert("problem_description", problem_description);
 
-        Ok(format!("{}\n{}", coding_summary.template, problem_template))
+        let template_name = if is_polyglot { "problem_description_prompt_polyglot" } else { "problem_description_prompt" };
+        self.render_template(template_name, &context)
     }
 
     /// List all available template names
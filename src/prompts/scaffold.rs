@@ -0,0 +1,162 @@
+use crate::prompts::{PromptTemplate, TemplateContext};
+use crate::DgmResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Category a [`ProjectTemplate`] scaffolds, used to group the registry for
+/// [`TemplateRegistry::list_templates_by_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppType {
+    CliTool,
+    ApiService,
+    WebApp,
+    Library,
+}
+
+/// One typed, user-selectable knob a [`ProjectTemplate`] exposes. Supplied values are validated
+/// against the declared `kind` by [`TemplateOption::validate`] before a scaffold is instantiated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TemplateOption {
+    Bool { default: bool },
+    Enum { default: String, allowed: Vec<String> },
+    String { default: String },
+}
+
+impl TemplateOption {
+    /// This option's value as rendered into a file template's context, when the caller doesn't
+    /// supply one of its own.
+    fn default_as_string(&self) -> String {
+        match self {
+            Self::Bool { default } => default.to_string(),
+            Self::Enum { default, .. } => default.clone(),
+            Self::String { default } => default.clone(),
+        }
+    }
+
+    /// Check `value` against this option's declared shape: a `Bool` option only accepts `"true"`/
+    /// `"false"`, an `Enum` option only accepts one of its `allowed` values, a `String` option
+    /// accepts anything.
+    fn validate(&self, name: &str, value: &str) -> DgmResult<()> {
+        match self {
+            Self::Bool { .. } => {
+                if value != "true" && value != "false" {
+                    anyhow::bail!("option '{name}' is a bool; got '{value}' (expected 'true' or 'false')");
+                }
+            }
+            Self::Enum { allowed, .. } => {
+                if !allowed.iter().any(|candidate| candidate == value) {
+                    anyhow::bail!("option '{name}' got '{value}', which isn't one of the allowed values: {allowed:?}");
+                }
+            }
+            Self::String { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// One file a [`ProjectTemplate`] emits: `path` is rendered relative to the scaffold's output
+/// directory, `contents` is a [`PromptTemplate`] rendered against the chosen options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaffoldFile {
+    pub path: PathBuf,
+    pub contents: PromptTemplate,
+}
+
+/// A project skeleton: a stable id, an [`AppType`] category, a human summary, a declared set of
+/// typed [`TemplateOption`]s, and the [`ScaffoldFile`]s to emit once those options are chosen.
+/// Modeled after scaffolding tools (`cargo new`, `create-react-app`) rather than the plain prompt
+/// strings [`crate::prompts::PromptManager`] otherwise serves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub app_type: AppType,
+    pub summary: String,
+    pub options: HashMap<String, TemplateOption>,
+    pub files: Vec<ScaffoldFile>,
+}
+
+/// Registry of [`ProjectTemplate`]s, sibling to [`crate::prompts::PromptManager`]'s prompt-string
+/// templates. Starts empty; scaffolds are added via [`Self::add_template`] or loaded in bulk via
+/// [`Self::load_templates_from_file`], the same JSON-file convention `PromptManager` uses for its
+/// own templates.
+#[derive(Default)]
+pub struct TemplateRegistry {
+    templates: HashMap<String, ProjectTemplate>,
+}
+
+impl TemplateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) one scaffold.
+    pub fn add_template(&mut self, template: ProjectTemplate) {
+        self.templates.insert(template.id.clone(), template);
+    }
+
+    /// Load scaffolds from a JSON file (an array of [`ProjectTemplate`]s), the same shape
+    /// `PromptManager::load_templates_from_file` reads for its own templates.
+    pub async fn load_templates_from_file(&mut self, path: &std::path::Path) -> DgmResult<()> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read scaffold templates file {:?}: {e}", path))?;
+
+        let templates: Vec<ProjectTemplate> = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse scaffold templates JSON {:?}: {e}", path))?;
+
+        for template in templates {
+            self.add_template(template);
+        }
+
+        Ok(())
+    }
+
+    pub fn get_template(&self, id: &str) -> Option<&ProjectTemplate> {
+        self.templates.get(id)
+    }
+
+    /// All scaffolds registered under `app_type`, in no particular order.
+    pub fn list_templates_by_type(&self, app_type: AppType) -> Vec<&ProjectTemplate> {
+        self.templates.values().filter(|template| template.app_type == app_type).collect()
+    }
+
+    /// The declared options for `id`, for a caller to present to a user before instantiating.
+    pub fn describe_options(&self, id: &str) -> DgmResult<&HashMap<String, TemplateOption>> {
+        let template = self.templates.get(id).ok_or_else(|| anyhow::anyhow!("Scaffold template '{}' not found", id))?;
+        Ok(&template.options)
+    }
+
+    /// Render every file in scaffold `id`, merging `options` over the template's declared defaults
+    /// and rejecting any supplied option that's unknown or fails its declared shape. Returns each
+    /// file's path paired with its rendered contents; it's up to the caller to actually write them
+    /// to disk.
+    pub fn instantiate(&self, id: &str, options: &HashMap<String, String>) -> DgmResult<Vec<(PathBuf, String)>> {
+        let template = self.templates.get(id).ok_or_else(|| anyhow::anyhow!("Scaffold template '{}' not found", id))?;
+
+        for name in options.keys() {
+            if !template.options.contains_key(name) {
+                anyhow::bail!("scaffold '{}' has no option named '{}'", id, name);
+            }
+        }
+
+        let mut context = TemplateContext::new();
+        for (name, declared) in &template.options {
+            let default_value = declared.default_as_string();
+            let value = options.get(name).map(String::as_str).unwrap_or(default_value.as_str());
+            declared.validate(name, value)?;
+            context.insert(name.clone(), value.to_string());
+        }
+
+        template
+            .files
+            .iter()
+            .map(|file| {
+                let rendered = file.contents.render(&context)?;
+                Ok((file.path.clone(), rendered))
+            })
+            .collect()
+    }
+}
@@ -1,25 +1,360 @@
 use super::{Tool, ToolInfo};
+use crate::config::BashConfig;
 use crate::DgmResult;
 use async_trait::async_trait;
+use bytes::Bytes;
+use nix::pty::{openpty, Winsize};
+use nix::sys::termios;
 use serde_json::{json, Value};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::os::unix::process::CommandExt;
 use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::time::{Duration, Instant};
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 use tokio::time::timeout;
-// use tracing::debug;
 
-pub struct BashTool {
+/// A chunk of output forwarded to an [`OutputSink`] as soon as it is read, tagged by the
+/// channel it came from. A [`LocalBash`] session multiplexes stdout/stderr onto a single PTY
+/// (see [`BashSession`]), so it always tags chunks `Stdout`; [`RemoteBash`] keeps the two ssh
+/// pipes separate and tags each accordingly.
+#[derive(Debug, Clone)]
+pub enum OutputChunk {
+    Stdout(Bytes),
+    Stderr(Bytes),
+}
+
+/// Callback invoked with output chunks as they arrive, so a caller can report progress on a
+/// long-running command (e.g. a build or test suite) instead of it appearing hung until the
+/// sentinel/exit status shows up.
+pub type OutputSink = Arc<dyn Fn(OutputChunk) + Send + Sync>;
+
+/// The default wall-clock budget given to a single command, used whenever a caller doesn't
+/// override it via [`ShellBackend::run`]'s `timeout` argument.
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// RAII guard giving the evolution loop per-generation visibility into how much wall-clock time
+/// and how many failures the agent's shell commands consume. Armed on entry (recording a start
+/// counter), it is meant to be [`disarm`](Self::disarm)ed on every clean return path with the
+/// outcome that actually happened; if it is instead dropped still armed - the task was cancelled
+/// or panicked mid-command - the `Drop` impl records it as `abandoned` so those runs aren't
+/// silently missing from the metrics.
+struct ExecutionMetricsGuard {
+    start: Instant,
+    outcome: &'static str,
+}
+
+impl ExecutionMetricsGuard {
+    fn arm() -> Self {
+        metrics::counter!("dgm_bash_command_started_total").increment(1);
+        Self {
+            start: Instant::now(),
+            outcome: "abandoned",
+        }
+    }
+
+    /// Record the real outcome (`"completed"`, `"timed_out"`, or `"errored"`) for this run.
+    fn disarm(&mut self, outcome: &'static str) {
+        self.outcome = outcome;
+    }
+}
+
+impl Drop for ExecutionMetricsGuard {
+    fn drop(&mut self) {
+        metrics::counter!("dgm_bash_command_completed_total", "outcome" => self.outcome).increment(1);
+        metrics::histogram!("dgm_bash_command_duration_seconds", "outcome" => self.outcome)
+            .record(self.start.elapsed().as_secs_f64());
+    }
+}
+
+/// OS-level guardrails applied to every spawned bash command. A self-modifying agent can easily
+/// produce a fork bomb, an infinite-memory loop, or gigabytes of output; the wall-clock `timeout`
+/// passed to [`ShellBackend::run`] doesn't catch any of those.
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceLimits {
+    /// RLIMIT_CPU: maximum CPU time in seconds.
+    pub cpu_seconds: u64,
+    /// RLIMIT_AS: maximum virtual address space in bytes.
+    pub memory_bytes: u64,
+    /// RLIMIT_FSIZE: maximum size of any file the process creates, in bytes.
+    pub file_size_bytes: u64,
+    /// RLIMIT_NPROC: maximum number of processes/threads the user may own, guarding against fork bombs.
+    pub max_processes: u64,
+    /// Cap on the accumulated command output retained in memory before truncation.
+    pub output_cap_bytes: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self::from(&BashConfig::default())
+    }
+}
+
+impl From<&BashConfig> for ResourceLimits {
+    fn from(config: &BashConfig) -> Self {
+        Self {
+            cpu_seconds: config.cpu_seconds,
+            memory_bytes: config.memory_bytes,
+            file_size_bytes: config.file_size_bytes,
+            max_processes: config.max_processes,
+            output_cap_bytes: config.output_cap_bytes,
+        }
+    }
+}
+
+/// Runs shell commands on behalf of a [`BashTool`]. Abstracting over this lets DGM evaluate
+/// self-improvement candidates inside a sandbox other than the orchestrator's own shell: a
+/// [`LocalBash`] session today, a container or a [`RemoteBash`] host tomorrow.
+#[async_trait]
+pub trait ShellBackend: Send + Sync {
+    /// Run `cmd`, waiting at most `timeout` before giving up. Returns `(stdout, stderr, exit_code)`.
+    async fn run(&self, cmd: &str, timeout: Duration) -> DgmResult<(String, String, i32)> {
+        self.run_streaming(cmd, timeout, None).await
+    }
+
+    /// Like [`run`](Self::run), but also forwards output chunks to `sink` as they arrive rather
+    /// than only making them available once the command completes.
+    async fn run_streaming(
+        &self,
+        cmd: &str,
+        timeout: Duration,
+        sink: Option<OutputSink>,
+    ) -> DgmResult<(String, String, i32)>;
+}
+
+/// Runs commands in a persistent local PTY-backed [`BashSession`].
+pub struct LocalBash {
     session: Arc<Mutex<Option<BashSession>>>,
+    resource_limits: ResourceLimits,
 }
 
-impl BashTool {
+impl LocalBash {
     pub fn new() -> Self {
+        Self::with_limits(ResourceLimits::default())
+    }
+
+    pub fn with_limits(resource_limits: ResourceLimits) -> Self {
         Self {
             session: Arc::new(Mutex::new(None)),
+            resource_limits,
+        }
+    }
+}
+
+impl Default for LocalBash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ShellBackend for LocalBash {
+    async fn run_streaming(
+        &self,
+        cmd: &str,
+        timeout: Duration,
+        sink: Option<OutputSink>,
+    ) -> DgmResult<(String, String, i32)> {
+        let mut session_guard = self.session.lock().await;
+
+        if session_guard.is_none() {
+            let mut session = BashSession::new(self.resource_limits);
+            session.start().await?;
+            *session_guard = Some(session);
+        }
+
+        let session = session_guard.as_mut().unwrap();
+        session.timeout_duration = timeout;
+
+        match session.run(cmd, sink).await {
+            // The PTY merges stdout and stderr onto a single stream, so there is no separate
+            // stderr to report here.
+            Ok((output, exit_code)) => Ok((output, String::new(), exit_code)),
+            Err(e) => {
+                // Session might be broken, reset it
+                *session_guard = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Connection details for a host reachable over SSH.
+#[derive(Debug, Clone)]
+pub struct RemoteHost {
+    pub host: String,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    pub identity_file: Option<String>,
+}
+
+/// Runs commands on a remote host by shelling out to the system `ssh` client, so evaluation of a
+/// candidate's self-edits can happen inside an isolated remote sandbox rather than on the
+/// orchestrator's own machine.
+pub struct RemoteBash {
+    host: RemoteHost,
+}
+
+impl RemoteBash {
+    pub fn new(host: RemoteHost) -> Self {
+        Self { host }
+    }
+}
+
+#[async_trait]
+impl ShellBackend for RemoteBash {
+    async fn run_streaming(
+        &self,
+        cmd: &str,
+        timeout_duration: Duration,
+        sink: Option<OutputSink>,
+    ) -> DgmResult<(String, String, i32)> {
+        let mut args: Vec<String> = Vec::new();
+        if let Some(port) = self.host.port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        if let Some(identity_file) = &self.host.identity_file {
+            args.push("-i".to_string());
+            args.push(identity_file.clone());
+        }
+        args.push("-o".to_string());
+        args.push("BatchMode=yes".to_string());
+
+        let target = match &self.host.user {
+            Some(user) => format!("{}@{}", user, self.host.host),
+            None => self.host.host.clone(),
+        };
+        args.push(target);
+        args.push(cmd.to_string());
+
+        let mut child = Command::new("ssh")
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to spawn ssh command: {}", e))?;
+
+        let child_stdout = child.stdout.take().ok_or_else(|| anyhow::anyhow!("ssh child missing stdout"))?;
+        let child_stderr = child.stderr.take().ok_or_else(|| anyhow::anyhow!("ssh child missing stderr"))?;
+
+        // Read stdout and stderr concurrently rather than one after the other, so a command that
+        // floods one stream can't starve the other's pipe buffer and stall the whole read.
+        let drain = async {
+            let mut stdout_reader = BufReader::new(child_stdout);
+            let mut stderr_reader = BufReader::new(child_stderr);
+            let mut stdout_buf = Vec::new();
+            let mut stderr_buf = Vec::new();
+            let mut stdout_chunk = [0u8; 4096];
+            let mut stderr_chunk = [0u8; 4096];
+            let mut stdout_done = false;
+            let mut stderr_done = false;
+
+            while !stdout_done || !stderr_done {
+                tokio::select! {
+                    n = stdout_reader.read(&mut stdout_chunk), if !stdout_done => {
+                        let n = n.map_err(|e| anyhow::anyhow!("Failed to read ssh stdout: {}", e))?;
+                        if n == 0 {
+                            stdout_done = true;
+                        } else {
+                            stdout_buf.extend_from_slice(&stdout_chunk[..n]);
+                            if let Some(sink) = &sink {
+                                sink(OutputChunk::Stdout(Bytes::copy_from_slice(&stdout_chunk[..n])));
+                            }
+                        }
+                    }
+                    n = stderr_reader.read(&mut stderr_chunk), if !stderr_done => {
+                        let n = n.map_err(|e| anyhow::anyhow!("Failed to read ssh stderr: {}", e))?;
+                        if n == 0 {
+                            stderr_done = true;
+                        } else {
+                            stderr_buf.extend_from_slice(&stderr_chunk[..n]);
+                            if let Some(sink) = &sink {
+                                sink(OutputChunk::Stderr(Bytes::copy_from_slice(&stderr_chunk[..n])));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let status = child
+                .wait()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to wait for ssh command: {}", e))?;
+
+            Ok::<_, anyhow::Error>((stdout_buf, stderr_buf, status.code().unwrap_or(-1)))
+        };
+
+        let (stdout_buf, stderr_buf, exit_code) = timeout(timeout_duration, drain)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Timed out: ssh command has not returned in {} seconds",
+                    timeout_duration.as_secs()
+                )
+            })??;
+
+        let stdout = String::from_utf8_lossy(&stdout_buf).to_string();
+        let stderr = String::from_utf8_lossy(&stderr_buf).to_string();
+
+        Ok((stdout, stderr, exit_code))
+    }
+}
+
+pub struct BashTool {
+    backend: Arc<dyn ShellBackend>,
+}
+
+impl BashTool {
+    /// Build a tool backed by a local PTY session, the default used by [`super::ToolRegistry`].
+    pub fn new() -> Self {
+        Self::with_backend(Arc::new(LocalBash::new()))
+    }
+
+    /// Build a tool against any [`ShellBackend`], e.g. a [`RemoteBash`]. `DgmConfig` has no
+    /// backend setting; [`Self::new`] always uses a [`LocalBash`].
+    pub fn with_backend(backend: Arc<dyn ShellBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Like [`Tool::execute`], but forwards output chunks to `sink` as they arrive so a caller
+    /// can report progress on a long-running command instead of it appearing hung.
+    pub async fn execute_streaming(&self, input: Value, sink: Option<OutputSink>) -> DgmResult<String> {
+        let mut metrics_guard = ExecutionMetricsGuard::arm();
+
+        let command = input["command"].as_str().ok_or_else(|| {
+            metrics_guard.disarm("errored");
+            anyhow::anyhow!("Missing 'command' parameter")
+        })?;
+
+        let run_result = self
+            .backend
+            .run_streaming(command, DEFAULT_COMMAND_TIMEOUT, sink)
+            .await;
+
+        let (stdout, stderr, exit_code) = match run_result {
+            Ok(result) => {
+                metrics_guard.disarm("completed");
+                result
+            }
+            Err(e) => {
+                let outcome = if e.to_string().contains("Timed out") { "timed_out" } else { "errored" };
+                metrics_guard.disarm(outcome);
+                return Err(e);
+            }
+        };
+
+        let mut result = stdout.trim().to_string();
+        if !stderr.trim().is_empty() {
+            result.push_str(&format!("\n{}", stderr.trim()));
+        }
+        if exit_code != 0 {
+            result.push_str(&format!("\nExit code: {}", exit_code));
         }
+        Ok(result)
     }
 }
 
@@ -51,60 +386,31 @@ impl Tool for BashTool {
     }
 
     async fn execute(&self, input: Value) -> DgmResult<String> {
-        let command = input["command"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
-
-        let mut session_guard = self.session.lock().await;
-        
-        // Initialize session if it doesn't exist
-        if session_guard.is_none() {
-            let mut session = BashSession::new();
-            session.start().await?;
-            *session_guard = Some(session);
-        }
-
-        let session = session_guard.as_mut().unwrap();
-        let result = session.run(command).await;
-        
-        match result {
-            Ok((output, error)) => {
-                let filtered_error = filter_error(&error);
-                let mut result = String::new();
-                if !output.is_empty() {
-                    result.push_str(&output);
-                }
-                if !filtered_error.is_empty() {
-                    result.push_str("\nError:\n");
-                    result.push_str(&filtered_error);
-                }
-                Ok(result.trim().to_string())
-            }
-            Err(e) => {
-                // Session might be broken, reset it
-                *session_guard = None;
-                Err(e)
-            }
-        }
+        self.execute_streaming(input, None).await
     }
 }
 
+/// A PTY-backed bash session. Using a real pseudo-terminal (rather than piped stdio) means bash
+/// believes it has a controlling terminal, so programs that probe for a TTY (editors, REPLs,
+/// pagers, `git`, `pytest --color`) behave the same way they would for an interactive user.
 struct BashSession {
     process: Option<Child>,
+    master_fd: Option<Arc<AsyncFd<OwnedFd>>>,
     timeout_duration: Duration,
     sentinel: String,
-    output_delay: Duration,
     timed_out: bool,
+    resource_limits: ResourceLimits,
 }
 
 impl BashSession {
-    fn new() -> Self {
+    fn new(resource_limits: ResourceLimits) -> Self {
         Self {
             process: None,
+            master_fd: None,
             timeout_duration: Duration::from_secs(120),
             sentinel: "<<exit>>".to_string(),
-            output_delay: Duration::from_millis(200),
             timed_out: false,
+            resource_limits,
         }
     }
 
@@ -113,31 +419,102 @@ impl BashSession {
             return Ok(());
         }
 
-        let child = Command::new("/bin/bash")
+        let winsize = Winsize {
+            ws_row: 50,
+            ws_col: 200,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        let pty = openpty(Some(&winsize), None)
+            .map_err(|e| anyhow::anyhow!("Failed to open PTY: {}", e))?;
+
+        // Put the slave side in raw-ish mode so bash's line editing doesn't echo/garble output.
+        if let Ok(mut termios) = termios::tcgetattr(&pty.slave) {
+            termios::cfmakeraw(&mut termios);
+            let _ = termios::tcsetattr(&pty.slave, termios::SetArg::TCSANOW, &termios);
+        }
+
+        let slave_stdin = Stdio::from(pty.slave.try_clone().map_err(|e| anyhow::anyhow!(e))?);
+        let slave_stdout = Stdio::from(pty.slave.try_clone().map_err(|e| anyhow::anyhow!(e))?);
+        let slave_stderr = Stdio::from(pty.slave);
+
+        let limits = self.resource_limits;
+        let mut command = Command::new("/bin/bash");
+        command
             .arg("-i")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .env("TERM", "xterm-256color")
+            .stdin(slave_stdin)
+            .stdout(slave_stdout)
+            .stderr(slave_stderr);
+
+        // SAFETY: this closure runs in the forked child between fork() and exec(), so it must
+        // stay async-signal-safe - raw setrlimit calls only, no allocation.
+        unsafe {
+            command.pre_exec(move || {
+                Self::apply_resource_limit(libc::RLIMIT_CPU, limits.cpu_seconds)?;
+                Self::apply_resource_limit(libc::RLIMIT_AS, limits.memory_bytes)?;
+                Self::apply_resource_limit(libc::RLIMIT_FSIZE, limits.file_size_bytes)?;
+                Self::apply_resource_limit(libc::RLIMIT_NPROC, limits.max_processes)?;
+                Ok(())
+            });
+        }
+
+        let child = command
             .spawn()
             .map_err(|e| anyhow::anyhow!("Failed to start bash process: {}", e))?;
 
+        let master_fd = AsyncFd::new(pty.master)
+            .map_err(|e| anyhow::anyhow!("Failed to register PTY master fd: {}", e))?;
+
         self.process = Some(child);
+        self.master_fd = Some(Arc::new(master_fd));
+        Ok(())
+    }
+
+    /// Resize the PTY so full-screen programs (pagers, editors) lay out correctly.
+    fn set_window_size(&self, rows: u16, cols: u16) -> DgmResult<()> {
+        let master = self
+            .master_fd
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Bash session not started"))?;
+
+        let winsize = Winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        // SAFETY: TIOCSWINSZ with a valid Winsize is the documented way to resize a PTY.
+        let ret = unsafe {
+            libc::ioctl(master.get_ref().as_raw_fd(), libc::TIOCSWINSZ, &winsize as *const _)
+        };
+        if ret != 0 {
+            return Err(anyhow::anyhow!("Failed to set PTY window size: {}", std::io::Error::last_os_error()));
+        }
         Ok(())
     }
 
     fn stop(&mut self) {
         if let Some(mut process) = self.process.take() {
             if process.try_wait().unwrap_or(None).is_none() {
-                let _ = process.kill();
+                let _ = process.start_kill();
             }
         }
+        self.master_fd = None;
     }
 
-    async fn run(&mut self, command: &str) -> DgmResult<(String, String)> {
-        let process = self.process.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Bash session not started"))?;
+    async fn run(&mut self, command: &str, sink: Option<OutputSink>) -> DgmResult<(String, i32)> {
+        let mut metrics_guard = ExecutionMetricsGuard::arm();
+
+        if self.process.is_none() {
+            metrics_guard.disarm("errored");
+            return Err(anyhow::anyhow!("Bash session not started"));
+        }
 
         if self.timed_out {
+            metrics_guard.disarm("timed_out");
             return Err(anyhow::anyhow!(
                 "Timed out: bash has not returned in {} seconds and must be restarted",
                 self.timeout_duration.as_secs()
@@ -145,81 +522,101 @@ impl BashSession {
         }
 
         // Check if process is still alive
-        if let Ok(Some(_)) = process.try_wait() {
+        if let Ok(Some(_)) = self.process.as_mut().unwrap().try_wait() {
+            metrics_guard.disarm("errored");
             return Err(anyhow::anyhow!("Bash process has exited"));
         }
 
-        let stdin = process.stdin.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get stdin"))?;
-
-        // Send command with sentinel
-        let command_with_sentinel = format!("{}; echo '{}'\n", command, self.sentinel);
-        stdin.write_all(command_with_sentinel.as_bytes()).await
-            .map_err(|e| anyhow::anyhow!("Failed to write command: {}", e))?;
-        stdin.flush().await
-            .map_err(|e| anyhow::anyhow!("Failed to flush stdin: {}", e))?;
-
-        // Read output with timeout
-        let read_operation = async {
-            let stdout = process.stdout.as_mut()
-                .ok_or_else(|| anyhow::anyhow!("Failed to get stdout"))?;
-            let stderr = process.stderr.as_mut()
-                .ok_or_else(|| anyhow::anyhow!("Failed to get stderr"))?;
-
-            let mut stdout_reader = BufReader::new(stdout);
-            let mut stderr_reader = BufReader::new(stderr);
-            
+        let master = match self.master_fd.clone() {
+            Some(master) => master,
+            None => {
+                metrics_guard.disarm("errored");
+                return Err(anyhow::anyhow!("PTY master not available"));
+            }
+        };
+
+        // Send command with sentinel, including the real exit status ($?) so the caller can
+        // tell success from failure instead of heuristically scanning output. stdout and stderr
+        // are merged onto the single PTY stream, so there is no separate stderr to read back.
+        let command_with_sentinel = format!("{}; echo \"{}:$?\"\n", command, self.sentinel);
+        if let Err(e) = Self::write_all(&master, command_with_sentinel.as_bytes()).await {
+            metrics_guard.disarm("errored");
+            return Err(e);
+        }
+
+        let sentinel = self.sentinel.clone();
+        let output_cap_bytes = self.resource_limits.output_cap_bytes;
+        let read_operation = async move {
             let mut output = String::new();
-            let mut error = String::new();
-            let mut stdout_line = String::new();
-            let mut stderr_line = String::new();
+            let mut output_truncated = false;
+            // Short rolling window of the most recent bytes, used to find the sentinel even
+            // after `output` itself has stopped growing because it hit `output_cap_bytes`.
+            let mut tail = String::new();
+            let max_tail_len = sentinel.len() + 32;
+            let mut buf = [0u8; 4096];
 
             loop {
-                tokio::time::sleep(self.output_delay).await;
-
-                // Try to read from stdout
-                match stdout_reader.read_line(&mut stdout_line).await {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        if stdout_line.trim() == self.sentinel {
-                            break;
-                        }
-                        output.push_str(&stdout_line);
-                        stdout_line.clear();
-                    }
-                    Err(_) => {} // Continue on error
+                let n = Self::read_some(&master, &mut buf).await?;
+                if n == 0 {
+                    break;
+                }
+                if let Some(sink) = &sink {
+                    sink(OutputChunk::Stdout(Bytes::copy_from_slice(&buf[..n])));
                 }
 
-                // Try to read from stderr
-                match stderr_reader.read_line(&mut stderr_line).await {
-                    Ok(0) => {} // EOF, continue
-                    Ok(_) => {
-                        error.push_str(&stderr_line);
-                        stderr_line.clear();
-                    }
-                    Err(_) => {} // Continue on error
+                let chunk = String::from_utf8_lossy(&buf[..n]);
+
+                tail.push_str(&chunk);
+                if tail.len() > max_tail_len {
+                    let excess = tail.len() - max_tail_len;
+                    tail.drain(..excess);
+                }
+
+                if !output_truncated {
+                    output.push_str(&chunk);
                 }
 
-                if output.contains(&self.sentinel) {
-                    // Remove sentinel from output
-                    if let Some(pos) = output.find(&self.sentinel) {
-                        output.truncate(pos);
+                if let Some(pos) = tail.find(&sentinel) {
+                    let status_tail = &tail[pos + sentinel.len()..];
+                    let status_str = status_tail
+                        .trim_start_matches(':')
+                        .lines()
+                        .next()
+                        .unwrap_or("")
+                        .trim();
+                    let exit_code = status_str.parse::<i32>().unwrap_or(-1);
+
+                    if !output_truncated {
+                        if let Some(out_pos) = output.rfind(&sentinel) {
+                            output.truncate(out_pos);
+                        }
                     }
-                    break;
+                    return Ok::<(String, i32), anyhow::Error>((output, exit_code));
+                }
+
+                if !output_truncated && output.len() > output_cap_bytes {
+                    Self::truncate_at_char_boundary(&mut output, output_cap_bytes);
+                    output.push_str(&format!("\n[output truncated at {} bytes]\n", output_cap_bytes));
+                    output_truncated = true;
                 }
             }
 
-            Ok::<(String, String), anyhow::Error>((output.trim().to_string(), error.trim().to_string()))
+            Err(anyhow::anyhow!("Bash session closed before sentinel was seen"))
         };
 
         match timeout(self.timeout_duration, read_operation).await {
-            Ok(Ok(result)) => Ok(result),
+            Ok(Ok(result)) => {
+                metrics_guard.disarm("completed");
+                Ok(result)
+            }
             Ok(Err(e)) => {
                 self.timed_out = true;
+                metrics_guard.disarm("errored");
                 Err(e)
             }
             Err(_) => {
                 self.timed_out = true;
+                metrics_guard.disarm("timed_out");
                 Err(anyhow::anyhow!(
                     "Timed out: bash has not returned in {} seconds",
                     self.timeout_duration.as_secs()
@@ -227,39 +624,69 @@ impl BashSession {
             }
         }
     }
-}
 
-impl Drop for BashSession {
-    fn drop(&mut self) {
-        self.stop();
+    /// Truncate `s` to at most `max_bytes` bytes without splitting a multi-byte UTF-8 character.
+    fn truncate_at_char_boundary(s: &mut String, max_bytes: usize) {
+        let mut idx = max_bytes.min(s.len());
+        while idx > 0 && !s.is_char_boundary(idx) {
+            idx -= 1;
+        }
+        s.truncate(idx);
     }
-}
 
-fn filter_error(error: &str) -> String {
-    let mut filtered_lines = Vec::new();
-    let error_lines: Vec<&str> = error.lines().collect();
-    let mut i = 0;
-
-    while i < error_lines.len() {
-        let line = error_lines[i];
+    /// Apply a single `setrlimit` for both the soft and hard limit. Called from a `pre_exec`
+    /// closure, so it must not allocate or do anything else that isn't async-signal-safe.
+    fn apply_resource_limit(resource: libc::__rlimit_resource_t, value: u64) -> std::io::Result<()> {
+        let rlim = libc::rlimit {
+            rlim_cur: value as libc::rlim_t,
+            rlim_max: value as libc::rlim_t,
+        };
+        let ret = unsafe { libc::setrlimit(resource, &rlim) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
 
-        // Skip ioctl errors and related lines
-        if line.contains("Inappropriate ioctl for device") {
-            i += 3;
-            if i < error_lines.len() && error_lines[i].contains("<<exit>>") {
-                i += 1;
-            }
-            while i < error_lines.len() - 1 {
-                filtered_lines.push(error_lines[i]);
-                i += 1;
+    async fn write_all(master: &Arc<AsyncFd<OwnedFd>>, data: &[u8]) -> DgmResult<()> {
+        loop {
+            let mut guard = master
+                .writable()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to wait for PTY writable: {}", e))?;
+
+            match guard.try_io(|inner| {
+                use std::io::Write as _;
+                let mut std_file = std::fs::File::from(inner.get_ref().try_clone()?);
+                std_file.write_all(data)
+            }) {
+                Ok(result) => return result.map_err(|e: std::io::Error| anyhow::anyhow!(e)),
+                Err(_would_block) => continue,
             }
-            i += 1;
-            continue;
         }
+    }
 
-        filtered_lines.push(line);
-        i += 1;
+    async fn read_some(master: &Arc<AsyncFd<OwnedFd>>, buf: &mut [u8]) -> DgmResult<usize> {
+        loop {
+            let mut guard = master
+                .readable()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to wait for PTY readable: {}", e))?;
+
+            match guard.try_io(|inner| {
+                use std::io::Read as _;
+                let mut std_file = std::fs::File::from(inner.get_ref().try_clone()?);
+                std_file.read(buf)
+            }) {
+                Ok(result) => return result.map_err(|e: std::io::Error| anyhow::anyhow!(e)),
+                Err(_would_block) => continue,
+            }
+        }
     }
+}
 
-    filtered_lines.join("\n").trim().to_string()
+impl Drop for BashSession {
+    fn drop(&mut self) {
+        self.stop();
+    }
 }
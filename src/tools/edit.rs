@@ -1,17 +1,57 @@
+use super::fs::{Fs, RealFs};
 use super::{Tool, ToolInfo};
+use crate::utils::unified_diff;
 use crate::DgmResult;
 use async_trait::async_trait;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tokio::fs;
-use tokio::process::Command;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 // use tracing::debug;
 
-pub struct EditTool;
+/// Custom text editor tool, backed by an [`Fs`]. Interior mutability on `history` is required
+/// because [`Tool::execute`] takes `&self`, not `&mut self`.
+pub struct EditTool {
+    fs: Arc<dyn Fs>,
+    history: Mutex<HashMap<PathBuf, Vec<String>>>,
+}
 
 impl EditTool {
+    /// Build a tool backed by the real filesystem, the default used by [`super::ToolRegistry`].
     pub fn new() -> Self {
-        Self
+        Self::with_fs(Arc::new(RealFs))
+    }
+
+    /// Build a tool against any [`Fs`], e.g. a [`super::fs::SandboxFs`] confining it to the
+    /// agent's workspace, or a [`super::fs::FakeFs`] in tests.
+    pub fn with_fs(fs: Arc<dyn Fs>) -> Self {
+        Self {
+            fs,
+            history: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Snapshot a file's current on-disk content onto its undo stack before overwriting it,
+    /// so [`Self::undo_edit`] has something to restore. A file with no prior content (about to
+    /// be created) has nothing to snapshot.
+    async fn snapshot(&self, path: &Path) {
+        if let Ok(content) = self.fs.read_to_string(path).await {
+            self.push_history(path, content).await;
+        }
+    }
+
+    /// Push `content` onto `path`'s undo stack directly, for callers that already have the
+    /// prior on-disk content in hand and don't need [`Self::snapshot`] to re-read it.
+    async fn push_history(&self, path: &Path, content: String) {
+        let mut history = self.history.lock().await;
+        history.entry(path.to_path_buf()).or_default().push(content);
+    }
+}
+
+impl Default for EditTool {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -27,14 +67,17 @@ impl Tool for EditTool {
 * The `create` command cannot be used if the specified `path` already exists as a file.
 * If a `command` generates a long output, it will be truncated and marked with `<response clipped>`.
 * The `edit` command overwrites the entire file with the provided `file_text`.
-* No partial/line-range edits or partial viewing are supported."#.to_string(),
+* The `str_replace` command replaces a unique occurrence of `old_str` with `new_str`; it fails if `old_str` doesn't appear exactly once.
+* The `insert` command inserts `new_str` after the 1-indexed `insert_line` (use `0` to prepend to the file).
+* `view` accepts an optional `view_range` of `[start_line, end_line]` (1-indexed, inclusive); use `-1` as `end_line` to view through the end of the file.
+* The `undo_edit` command reverts the last `edit`/`str_replace`/`insert` made to `path`; a file made by `create` cannot be undone."#.to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "command": {
                         "type": "string",
-                        "enum": ["view", "create", "edit"],
-                        "description": "The command to run: `view`, `create`, or `edit`."
+                        "enum": ["view", "create", "edit", "str_replace", "insert", "undo_edit"],
+                        "description": "The command to run: `view`, `create`, `edit`, `str_replace`, `insert`, or `undo_edit`."
                     },
                     "path": {
                         "description": "Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`.",
@@ -43,6 +86,23 @@ impl Tool for EditTool {
                     "file_text": {
                         "description": "Required parameter of `create` or `edit` command, containing the content for the entire file.",
                         "type": "string"
+                    },
+                    "view_range": {
+                        "description": "Optional parameter of `view` command, a list of two integers `[start_line, end_line]` (1-indexed, inclusive). `end_line` of `-1` means through the end of the file.",
+                        "type": "array",
+                        "items": { "type": "integer" }
+                    },
+                    "old_str": {
+                        "description": "Required parameter of `str_replace` command, the exact text to replace (must appear exactly once in the file).",
+                        "type": "string"
+                    },
+                    "new_str": {
+                        "description": "Required parameter of `str_replace` command containing the replacement text, and of `insert` command containing the text to insert.",
+                        "type": "string"
+                    },
+                    "insert_line": {
+                        "description": "Required parameter of `insert` command. The new text is inserted after this 1-indexed line number; `0` prepends to the file.",
+                        "type": "integer"
                     }
                 },
                 "required": ["command", "path"]
@@ -54,15 +114,20 @@ impl Tool for EditTool {
         let command = input["command"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
-        
+
         let path_str = input["path"]
             .as_str()
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
-        
+
         let file_text = input["file_text"].as_str();
 
         match command {
-            "view" => self.view_path(path_str).await,
+            "view" => {
+                let view_range = input["view_range"]
+                    .as_array()
+                    .map(|arr| arr.iter().filter_map(|v| v.as_i64()).collect::<Vec<i64>>());
+                self.view_path(path_str, view_range).await
+            }
             "create" => {
                 let text = file_text
                     .ok_or_else(|| anyhow::anyhow!("Missing 'file_text' for create command"))?;
@@ -73,32 +138,54 @@ impl Tool for EditTool {
                     .ok_or_else(|| anyhow::anyhow!("Missing 'file_text' for edit command"))?;
                 self.edit_file(path_str, text).await
             }
+            "str_replace" => {
+                let old_str = input["old_str"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'old_str' for str_replace command"))?;
+                let new_str = input["new_str"].as_str().unwrap_or("");
+                self.str_replace(path_str, old_str, new_str).await
+            }
+            "insert" => {
+                let insert_line = input["insert_line"]
+                    .as_u64()
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'insert_line' for insert command"))?
+                    as usize;
+                let new_str = input["new_str"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'new_str' for insert command"))?;
+                self.insert(path_str, insert_line, new_str).await
+            }
+            "undo_edit" => self.undo_edit(path_str).await,
             _ => Err(anyhow::anyhow!("Unknown command: {}", command)),
         }
     }
 }
 
 impl EditTool {
-    async fn view_path(&self, path_str: &str) -> DgmResult<String> {
+    async fn view_path(&self, path_str: &str, view_range: Option<Vec<i64>>) -> DgmResult<String> {
         let path = self.validate_path(path_str, "view").await?;
 
-        if path.is_dir() {
+        if self.fs.metadata(&path).await?.is_dir {
+            if view_range.is_some() {
+                return Err(anyhow::anyhow!("`view_range` is not supported when viewing a directory."));
+            }
             self.view_directory(&path).await
         } else {
-            self.view_file(&path).await
+            self.view_file(&path, view_range).await
         }
     }
 
     async fn create_file(&self, path_str: &str, content: &str) -> DgmResult<String> {
         let path = self.validate_path(path_str, "create").await?;
-        
+
         // Create parent directories if they don't exist
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).await
-                .map_err(|e| anyhow::anyhow!("Failed to create parent directories: {}", e))?;
+            self.fs.create_dir_all(parent).await?;
         }
 
-        fs::write(&path, content).await
+        self.snapshot(&path).await;
+
+        self.fs.write(&path, content).await
             .map_err(|e| anyhow::anyhow!("Failed to create file: {}", e))?;
 
         Ok(format!("File created successfully at: {}", path_str))
@@ -107,10 +194,100 @@ impl EditTool {
     async fn edit_file(&self, path_str: &str, content: &str) -> DgmResult<String> {
         let path = self.validate_path(path_str, "edit").await?;
 
-        fs::write(&path, content).await
+        let old_content = self.fs.read_to_string(&path).await
+            .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))?;
+
+        self.push_history(&path, old_content.clone()).await;
+
+        self.fs.write(&path, content).await
             .map_err(|e| anyhow::anyhow!("Failed to edit file: {}", e))?;
 
-        Ok(format!("File at {} has been overwritten with new content.", path_str))
+        let diff = unified_diff(&old_content, content, 3);
+        Ok(format!(
+            "File at {} has been overwritten with new content.\n{}",
+            path_str, diff
+        ))
+    }
+
+    async fn str_replace(&self, path_str: &str, old_str: &str, new_str: &str) -> DgmResult<String> {
+        let path = self.validate_path(path_str, "str_replace").await?;
+
+        let content = self.fs.read_to_string(&path).await
+            .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))?;
+
+        let occurrences = content.matches(old_str).count();
+        if occurrences == 0 {
+            return Err(anyhow::anyhow!("No match found for `old_str` in {}.", path_str));
+        }
+        if occurrences > 1 {
+            return Err(anyhow::anyhow!(
+                "Found {} matches, `old_str` must be unique — add surrounding context.",
+                occurrences
+            ));
+        }
+
+        let new_content = content.replacen(old_str, new_str, 1);
+
+        self.push_history(&path, content.clone()).await;
+
+        self.fs.write(&path, &new_content).await
+            .map_err(|e| anyhow::anyhow!("Failed to write file: {}", e))?;
+
+        let diff = unified_diff(&content, &new_content, 3);
+        Ok(format!("The file {} has been edited.\n{}", path_str, diff))
+    }
+
+    async fn insert(&self, path_str: &str, insert_line: usize, new_str: &str) -> DgmResult<String> {
+        let path = self.validate_path(path_str, "insert").await?;
+
+        let content = self.fs.read_to_string(&path).await
+            .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))?;
+
+        let mut lines: Vec<&str> = content.lines().collect();
+        if insert_line > lines.len() {
+            return Err(anyhow::anyhow!(
+                "Invalid `insert_line` {}. It should be within the range of lines of the file: [0, {}]",
+                insert_line,
+                lines.len()
+            ));
+        }
+
+        let new_lines: Vec<&str> = new_str.split('\n').collect();
+        lines.splice(insert_line..insert_line, new_lines);
+
+        let mut new_content = lines.join("\n");
+        if content.ends_with('\n') || content.is_empty() {
+            new_content.push('\n');
+        }
+
+        self.push_history(&path, content.clone()).await;
+
+        self.fs.write(&path, &new_content).await
+            .map_err(|e| anyhow::anyhow!("Failed to write file: {}", e))?;
+
+        let diff = unified_diff(&content, &new_content, 3);
+        Ok(format!("The file {} has been edited.\n{}", path_str, diff))
+    }
+
+    /// Pop the most recent snapshot for `path_str` off its undo stack and write it back,
+    /// restoring the file to its state before the last `edit`/`str_replace`/`insert`. A
+    /// `create` pushes nothing (the file did not exist yet), so it is not undone.
+    async fn undo_edit(&self, path_str: &str) -> DgmResult<String> {
+        let path = self.validate_path(path_str, "edit").await?;
+
+        let previous = {
+            let mut history = self.history.lock().await;
+            history.get_mut(&path).and_then(|stack| stack.pop())
+        };
+
+        let Some(previous) = previous else {
+            return Err(anyhow::anyhow!("No edit history for {}", path_str));
+        };
+
+        self.fs.write(&path, &previous).await
+            .map_err(|e| anyhow::anyhow!("Failed to write file: {}", e))?;
+
+        Ok(self.format_output(&previous, path_str, 1))
     }
 
     async fn validate_path(&self, path_str: &str, command: &str) -> DgmResult<PathBuf> {
@@ -127,25 +304,24 @@ impl EditTool {
         match command {
             "view" => {
                 // Path must exist
-                if !path.exists() {
+                if !self.fs.exists(&path).await {
                     return Err(anyhow::anyhow!("The path {} does not exist.", path_str));
                 }
             }
             "create" => {
                 // Path must not exist
-                if path.exists() {
+                if self.fs.exists(&path).await {
                     return Err(anyhow::anyhow!(
                         "Cannot create new file; {} already exists.",
                         path_str
                     ));
                 }
             }
-            "edit" => {
+            "edit" | "str_replace" | "insert" => {
                 // Path must exist and must be a file
-                if !path.exists() {
-                    return Err(anyhow::anyhow!("The file {} does not exist.", path_str));
-                }
-                if path.is_dir() {
+                let metadata = self.fs.metadata(&path).await
+                    .map_err(|_| anyhow::anyhow!("The file {} does not exist.", path_str))?;
+                if metadata.is_dir {
                     return Err(anyhow::anyhow!(
                         "{} is a directory and cannot be edited as a file.",
                         path_str
@@ -160,33 +336,57 @@ impl EditTool {
         Ok(path)
     }
 
-    async fn view_file(&self, path: &Path) -> DgmResult<String> {
-        let content = fs::read_to_string(path).await
+    async fn view_file(&self, path: &Path, view_range: Option<Vec<i64>>) -> DgmResult<String> {
+        let content = self.fs.read_to_string(path).await
             .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))?;
 
-        let formatted = self.format_output(&content, path.to_string_lossy().as_ref(), 1);
-        Ok(formatted)
+        match view_range {
+            Some(range) => {
+                if range.len() != 2 {
+                    return Err(anyhow::anyhow!(
+                        "Invalid `view_range`. It should be a list of two integers, e.g. [11, 12]."
+                    ));
+                }
+
+                let lines: Vec<&str> = content.lines().collect();
+                let n_lines = lines.len() as i64;
+                let start = range[0];
+                let end = range[1];
+
+                if start < 1 || start > n_lines.max(1) {
+                    return Err(anyhow::anyhow!(
+                        "Invalid `view_range` {:?}: start line {} is outside [1, {}].",
+                        range, start, n_lines
+                    ));
+                }
+
+                let end_line = if end == -1 { n_lines } else { end };
+                if end_line < start || end_line > n_lines {
+                    return Err(anyhow::anyhow!(
+                        "Invalid `view_range` {:?}: end line {} should be >= start line and <= {}.",
+                        range, end, n_lines
+                    ));
+                }
+
+                let selected = lines[(start as usize - 1)..(end_line as usize)].join("\n");
+                let formatted = self.format_output(&selected, path.to_string_lossy().as_ref(), start as usize);
+                Ok(formatted)
+            }
+            None => Ok(self.format_output(&content, path.to_string_lossy().as_ref(), 1)),
+        }
     }
 
     async fn view_directory(&self, path: &Path) -> DgmResult<String> {
-        // Use find command to list files up to 2 levels deep, excluding hidden files
-        let output = Command::new("find")
-            .arg(path)
-            .arg("-maxdepth")
-            .arg("2")
-            .arg("-not")
-            .arg("-path")
-            .arg("*/\\.*")
-            .output()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to list directory: {}", e))?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Error listing directory: {}", error));
-        }
+        // Up to 2 levels deep; `self.fs`'s implementation decides what hidden/ignore-file
+        // filtering applies (the real filesystem's `RealFs` mirrors `fd`/`rg` semantics).
+        let entries = self.fs.read_dir(path, 2).await?;
+
+        let listing = entries
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
 
-        let listing = String::from_utf8_lossy(&output.stdout);
         Ok(format!(
             "Here's the files and directories up to 2 levels deep in {}, excluding hidden items:\n{}",
             path.display(),
@@ -0,0 +1,95 @@
+use super::{Tool, ToolInfo};
+use crate::DgmResult;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Declarative description of one externally-registered tool: a linter, type checker, search
+/// index, or anything else a user running a self-improvement experiment wants to expose to the
+/// agent without editing this crate. `command`/`args` may reference `{param}` placeholders that
+/// [`ExternalTool::execute`] fills in from the matching field of the parsed JSON input before
+/// spawning the subprocess.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalToolSpec {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// A [`Tool`] backed by an [`ExternalToolSpec`] rather than a compiled-in implementation like
+/// [`super::bash::BashTool`]/[`super::edit::EditTool`].
+pub struct ExternalTool {
+    spec: ExternalToolSpec,
+}
+
+impl ExternalTool {
+    pub fn new(spec: ExternalToolSpec) -> Self {
+        Self { spec }
+    }
+
+    /// Replace every `{param}` placeholder in `template` with the matching field of `input`,
+    /// rendered as its plain string value (strings are inserted verbatim; everything else is
+    /// rendered via its JSON representation).
+    fn substitute(template: &str, input: &Value) -> String {
+        let mut rendered = template.to_string();
+        if let Some(object) = input.as_object() {
+            for (key, value) in object {
+                let placeholder = format!("{{{}}}", key);
+                let value_str = value
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| value.to_string());
+                rendered = rendered.replace(&placeholder, &value_str);
+            }
+        }
+        rendered
+    }
+}
+
+#[async_trait]
+impl Tool for ExternalTool {
+    fn info(&self) -> ToolInfo {
+        ToolInfo {
+            name: self.spec.name.clone(),
+            description: self.spec.description.clone(),
+            input_schema: self.spec.input_schema.clone(),
+        }
+    }
+
+    async fn execute(&self, input: Value) -> DgmResult<String> {
+        let program = Self::substitute(&self.spec.command, &input);
+        let args: Vec<String> = self
+            .spec
+            .args
+            .iter()
+            .map(|arg| Self::substitute(arg, &input))
+            .collect();
+
+        debug!("Executing external tool '{}': {} {:?}", self.spec.name, program, args);
+
+        let output = Command::new(&program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to run external tool '{}': {}", self.spec.name, e))?;
+
+        let mut result = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.is_empty() {
+            result.push_str("\n--- stderr ---\n");
+            result.push_str(&stderr);
+        }
+        if !output.status.success() {
+            result.push_str(&format!("\n(exit status: {})", output.status));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,257 @@
+use crate::DgmResult;
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// Subset of file metadata [`super::edit::EditTool`] needs to validate a command: whether `path`
+/// exists at all, and if so whether it's a directory rather than a regular file.
+#[derive(Debug, Clone, Copy)]
+pub struct FsMetadata {
+    pub is_dir: bool,
+}
+
+/// Filesystem operations used by [`super::edit::EditTool`], abstracted the same way
+/// [`super::bash::ShellBackend`] abstracts command execution: a [`RealFs`] against the real
+/// filesystem in production, an in-memory [`FakeFs`] for fast deterministic tests, and a
+/// [`SandboxFs`] decorator to confine a self-modifying agent to its own workspace.
+#[async_trait]
+pub trait Fs: Send + Sync {
+    async fn exists(&self, path: &Path) -> bool;
+    async fn metadata(&self, path: &Path) -> DgmResult<FsMetadata>;
+    async fn read_to_string(&self, path: &Path) -> DgmResult<String>;
+    async fn write(&self, path: &Path, content: &str) -> DgmResult<()>;
+    async fn create_dir_all(&self, path: &Path) -> DgmResult<()>;
+    /// List every entry under `root` (not including `root` itself) up to `max_depth` levels
+    /// deep, sorted, with whatever hidden/ignore-file filtering the implementation applies.
+    async fn read_dir(&self, root: &Path, max_depth: usize) -> DgmResult<Vec<PathBuf>>;
+}
+
+/// Production [`Fs`] impl, backed directly by `tokio::fs` and the same ignore-aware directory
+/// walk (`fd`/`rg` semantics) `EditTool::view_directory` used before this abstraction existed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFs;
+
+#[async_trait]
+impl Fs for RealFs {
+    async fn exists(&self, path: &Path) -> bool {
+        tokio::fs::metadata(path).await.is_ok()
+    }
+
+    async fn metadata(&self, path: &Path) -> DgmResult<FsMetadata> {
+        let meta = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read metadata for {}: {}", path.display(), e))?;
+        Ok(FsMetadata { is_dir: meta.is_dir() })
+    }
+
+    async fn read_to_string(&self, path: &Path) -> DgmResult<String> {
+        tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))
+    }
+
+    async fn write(&self, path: &Path, content: &str) -> DgmResult<()> {
+        tokio::fs::write(path, content)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write file: {}", e))
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> DgmResult<()> {
+        tokio::fs::create_dir_all(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create parent directories: {}", e))
+    }
+
+    async fn read_dir(&self, root: &Path, max_depth: usize) -> DgmResult<Vec<PathBuf>> {
+        let root = root.to_path_buf();
+        tokio::task::spawn_blocking(move || -> DgmResult<Vec<PathBuf>> {
+            let mut entries = Vec::new();
+            let walker = ignore::WalkBuilder::new(&root)
+                .max_depth(Some(max_depth))
+                .hidden(true)
+                .git_ignore(true)
+                .git_global(true)
+                .git_exclude(true)
+                .build();
+
+            for entry in walker {
+                let entry = entry.map_err(|e| anyhow::anyhow!("Failed to walk directory: {}", e))?;
+                if entry.path() != root {
+                    entries.push(entry.path().to_path_buf());
+                }
+            }
+
+            entries.sort();
+            Ok(entries)
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Directory walk task panicked: {}", e))?
+    }
+}
+
+/// In-memory [`Fs`] for tests: regular files live in a map keyed by path, directories are
+/// tracked explicitly (created implicitly by [`Self::seed`] or explicitly via
+/// `create_dir_all`), so `EditTool`'s flows can be exercised without a `tempdir`.
+#[derive(Debug, Default)]
+pub struct FakeFs {
+    files: Mutex<HashMap<PathBuf, String>>,
+    dirs: Mutex<HashSet<PathBuf>>,
+}
+
+impl FakeFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a file's content (and its ancestor directories) before handing the `FakeFs` to the
+    /// code under test.
+    pub async fn seed(&self, path: impl Into<PathBuf>, content: impl Into<String>) {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            self.insert_dirs(parent).await;
+        }
+        self.files.lock().await.insert(path, content.into());
+    }
+
+    async fn insert_dirs(&self, path: &Path) {
+        let mut dirs = self.dirs.lock().await;
+        let mut current = PathBuf::new();
+        for component in path.components() {
+            current.push(component);
+            dirs.insert(current.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl Fs for FakeFs {
+    async fn exists(&self, path: &Path) -> bool {
+        self.files.lock().await.contains_key(path) || self.dirs.lock().await.contains(path)
+    }
+
+    async fn metadata(&self, path: &Path) -> DgmResult<FsMetadata> {
+        if self.files.lock().await.contains_key(path) {
+            return Ok(FsMetadata { is_dir: false });
+        }
+        if self.dirs.lock().await.contains(path) {
+            return Ok(FsMetadata { is_dir: true });
+        }
+        Err(anyhow::anyhow!("Failed to read metadata for {}: not found", path.display()))
+    }
+
+    async fn read_to_string(&self, path: &Path) -> DgmResult<String> {
+        self.files
+            .lock()
+            .await
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Failed to read file: {} not found", path.display()))
+    }
+
+    async fn write(&self, path: &Path, content: &str) -> DgmResult<()> {
+        if let Some(parent) = path.parent() {
+            self.insert_dirs(parent).await;
+        }
+        self.files.lock().await.insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> DgmResult<()> {
+        self.insert_dirs(path).await;
+        Ok(())
+    }
+
+    async fn read_dir(&self, root: &Path, max_depth: usize) -> DgmResult<Vec<PathBuf>> {
+        let files = self.files.lock().await;
+        let dirs = self.dirs.lock().await;
+        let mut entries: Vec<PathBuf> = files
+            .keys()
+            .chain(dirs.iter())
+            .filter(|path| {
+                path.strip_prefix(root)
+                    .map(|rel| !rel.as_os_str().is_empty() && rel.components().count() <= max_depth)
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        entries.sort();
+        Ok(entries)
+    }
+}
+
+/// Decorator confining every path an inner [`Fs`] sees to within a configured `root`, rejecting
+/// anything that resolves outside it after canonicalization so a self-modifying agent can't
+/// escape its own workspace via `..` or a symlink.
+pub struct SandboxFs<F> {
+    inner: F,
+    root: PathBuf,
+}
+
+impl<F: Fs> SandboxFs<F> {
+    /// `root` is canonicalized once up front and must already exist.
+    pub fn new(inner: F, root: impl AsRef<Path>) -> DgmResult<Self> {
+        let root = root.as_ref();
+        let canonical_root = root
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Failed to canonicalize sandbox root {}: {}", root.display(), e))?;
+        Ok(Self { inner, root: canonical_root })
+    }
+
+    /// Reject `path` unless it (or, for a not-yet-existing path, its nearest existing ancestor)
+    /// canonicalizes to somewhere inside `root`.
+    fn guard(&self, path: &Path) -> DgmResult<()> {
+        let mut probe = path.to_path_buf();
+        let resolved = loop {
+            match probe.canonicalize() {
+                Ok(resolved) => break resolved,
+                Err(_) => {
+                    if !probe.pop() {
+                        return Err(anyhow::anyhow!("Path {} escapes the sandbox root {}", path.display(), self.root.display()));
+                    }
+                }
+            }
+        };
+
+        if !resolved.starts_with(&self.root) {
+            return Err(anyhow::anyhow!(
+                "Path {} escapes the sandbox root {}",
+                path.display(),
+                self.root.display()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F: Fs> Fs for SandboxFs<F> {
+    async fn exists(&self, path: &Path) -> bool {
+        self.guard(path).is_ok() && self.inner.exists(path).await
+    }
+
+    async fn metadata(&self, path: &Path) -> DgmResult<FsMetadata> {
+        self.guard(path)?;
+        self.inner.metadata(path).await
+    }
+
+    async fn read_to_string(&self, path: &Path) -> DgmResult<String> {
+        self.guard(path)?;
+        self.inner.read_to_string(path).await
+    }
+
+    async fn write(&self, path: &Path, content: &str) -> DgmResult<()> {
+        self.guard(path)?;
+        self.inner.write(path, content).await
+    }
+
+    async fn create_dir_all(&self, path: &Path) -> DgmResult<()> {
+        self.guard(path)?;
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn read_dir(&self, root: &Path, max_depth: usize) -> DgmResult<Vec<PathBuf>> {
+        self.guard(root)?;
+        self.inner.read_dir(root, max_depth).await
+    }
+}
@@ -3,10 +3,15 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use tracing::debug;
 
 pub mod bash;
 pub mod edit;
+pub mod external;
+pub mod fs;
+pub mod schema;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolInfo {
@@ -23,12 +28,19 @@ pub trait Tool: Send + Sync {
 
 pub struct ToolRegistry {
     tools: HashMap<String, Box<dyn Tool>>,
+    /// Tools registered from config-declared [`external::ExternalToolSpec`]s, kept in
+    /// registration order (unlike `tools`, a `HashMap`) so a user can rely on the order their
+    /// extensions appear in `list_tools`/`get_tools_prompt` — the way extension-aware CLIs keep
+    /// their registration APIs multi-extension and order-stable from the start, in case a future
+    /// dynamic-loading ABI needs it.
+    extensions: Vec<Box<dyn Tool>>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             tools: HashMap::new(),
+            extensions: Vec::new(),
         };
 
         // Register built-in tools
@@ -38,21 +50,56 @@ impl ToolRegistry {
         registry
     }
 
+    /// Like [`Self::new`], but confines the `editor` tool to `sandbox_root` via
+    /// [`fs::SandboxFs`], so an agent working against this registry can't edit anything outside
+    /// its own workspace. `sandbox_root` must already exist.
+    pub fn with_sandbox_root(sandbox_root: &Path) -> DgmResult<Self> {
+        let mut registry = Self {
+            tools: HashMap::new(),
+            extensions: Vec::new(),
+        };
+
+        let sandboxed_fs = fs::SandboxFs::new(fs::RealFs, sandbox_root)?;
+        registry.register_tool(Box::new(bash::BashTool::new()));
+        registry.register_tool(Box::new(edit::EditTool::with_fs(Arc::new(sandboxed_fs))));
+
+        Ok(registry)
+    }
+
     pub fn register_tool(&mut self, tool: Box<dyn Tool>) {
         let name = tool.info().name.clone();
         self.tools.insert(name, tool);
     }
 
+    /// Register one config-declared external tool (see [`external::ExternalToolSpec`]), appended
+    /// after any already-registered extensions.
+    pub fn register_external_tool(&mut self, spec: external::ExternalToolSpec) {
+        self.extensions.push(Box::new(external::ExternalTool::new(spec)));
+    }
+
+    /// Register several external tools at once, in order, e.g. everything declared under
+    /// `DgmConfig::extra_tools`.
+    pub fn register_external_tools(&mut self, specs: Vec<external::ExternalToolSpec>) {
+        for spec in specs {
+            self.register_external_tool(spec);
+        }
+    }
+
     pub fn get_tool_info(&self, name: &str) -> Option<ToolInfo> {
-        self.tools.get(name).map(|tool| tool.info())
+        self.tools.get(name)
+            .map(|tool| tool.info())
+            .or_else(|| self.extensions.iter().find(|tool| tool.info().name == name).map(|tool| tool.info()))
     }
 
     pub fn list_tools(&self) -> Vec<ToolInfo> {
-        self.tools.values().map(|tool| tool.info()).collect()
+        let mut infos: Vec<ToolInfo> = self.tools.values().map(|tool| tool.info()).collect();
+        infos.extend(self.extensions.iter().map(|tool| tool.info()));
+        infos
     }
 
     pub async fn execute_tool(&self, name: &str, input: Value) -> DgmResult<String> {
         let tool = self.tools.get(name)
+            .or_else(|| self.extensions.iter().find(|tool| tool.info().name == name))
             .ok_or_else(|| anyhow::anyhow!("Tool '{}' not found", name))?;
 
         debug!("Executing tool '{}' with input: {:?}", name, input);
@@ -0,0 +1,223 @@
+use crate::DgmResult;
+use anyhow::Context;
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+use tokio::fs;
+
+/// JSON Schema keywords on an array property this repo's tool-schema convention disallows — see
+/// `coding_agent_summary_polyglot`'s "DOC: tool function schema" note, which this module's
+/// validator now enforces mechanically instead of just warning about in a prompt.
+const DISALLOWED_ARRAY_KEYWORDS: &[&str] = &["minItems", "maxItems"];
+
+/// One tool's `tool_info()` schema, parsed out of a tool source file rather than taken on faith.
+#[derive(Debug, Clone)]
+pub struct ToolSchema {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+    /// File this schema was extracted from, for diagnostics.
+    pub source_file: String,
+}
+
+/// A structured validation problem found in a [`ToolSchema`], instead of silently forwarding a
+/// malformed schema to the LLM. Mirrors the nesting mistakes `coding_agent_summary_polyglot`
+/// warns authors about by hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaIssue {
+    MissingField { tool: String, field: String },
+    NotAnObject { tool: String, field: String },
+    /// `required` was found nested inside `properties` instead of as its sibling.
+    RequiredNestedInProperties { tool: String },
+    PropertyMissingType { tool: String, property: String },
+    PropertyMissingDescription { tool: String, property: String },
+    DisallowedKeyword { tool: String, property: String, keyword: String },
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField { tool, field } => write!(f, "tool '{tool}': input_schema is missing '{field}'"),
+            Self::NotAnObject { tool, field } => write!(f, "tool '{tool}': '{field}' must be a JSON object"),
+            Self::RequiredNestedInProperties { tool } => {
+                write!(f, "tool '{tool}': 'required' must be a sibling of 'properties', not nested inside it")
+            }
+            Self::PropertyMissingType { tool, property } => write!(f, "tool '{tool}': property '{property}' has no 'type'"),
+            Self::PropertyMissingDescription { tool, property } => {
+                write!(f, "tool '{tool}': property '{property}' has no 'description'")
+            }
+            Self::DisallowedKeyword { tool, property, keyword } => {
+                write!(f, "tool '{tool}': property '{property}' uses disallowed keyword '{keyword}'")
+            }
+        }
+    }
+}
+
+/// Check the `required`-is-a-sibling-of-`properties`, every-property-has-`type`-and-`description`,
+/// and no-disallowed-array-keyword invariants described in [`SchemaIssue`]. Returns every issue
+/// found rather than stopping at the first one, so a caller can report (or fix) them all at once.
+pub fn validate_tool_schema(schema: &ToolSchema) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+    let tool = schema.name.clone();
+
+    let Some(root) = schema.input_schema.as_object() else {
+        issues.push(SchemaIssue::NotAnObject { tool, field: "input_schema".to_string() });
+        return issues;
+    };
+
+    if !root.contains_key("type") {
+        issues.push(SchemaIssue::MissingField { tool: tool.clone(), field: "type".to_string() });
+    }
+
+    let properties = match root.get("properties") {
+        Some(value) => match value.as_object() {
+            Some(obj) => Some(obj),
+            None => {
+                issues.push(SchemaIssue::NotAnObject { tool: tool.clone(), field: "properties".to_string() });
+                None
+            }
+        },
+        None => {
+            issues.push(SchemaIssue::MissingField { tool: tool.clone(), field: "properties".to_string() });
+            None
+        }
+    };
+
+    match root.get("required") {
+        Some(Value::Array(_)) => {}
+        Some(_) => issues.push(SchemaIssue::NotAnObject { tool: tool.clone(), field: "required (must be an array)".to_string() }),
+        None => issues.push(SchemaIssue::MissingField { tool: tool.clone(), field: "required".to_string() }),
+    }
+
+    if let Some(properties) = properties {
+        if properties.contains_key("required") {
+            issues.push(SchemaIssue::RequiredNestedInProperties { tool: tool.clone() });
+        }
+
+        for (property, property_schema) in properties {
+            let Some(property_obj) = property_schema.as_object() else {
+                continue;
+            };
+
+            if !property_obj.contains_key("type") {
+                issues.push(SchemaIssue::PropertyMissingType { tool: tool.clone(), property: property.clone() });
+            }
+            if !property_obj.contains_key("description") {
+                issues.push(SchemaIssue::PropertyMissingDescription { tool: tool.clone(), property: property.clone() });
+            }
+
+            if property_obj.get("type").and_then(Value::as_str) == Some("array") {
+                for keyword in DISALLOWED_ARRAY_KEYWORDS {
+                    if property_obj.contains_key(*keyword) {
+                        issues.push(SchemaIssue::DisallowedKeyword {
+                            tool: tool.clone(),
+                            property: property.clone(),
+                            keyword: (*keyword).to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+/// Find the first entry in `schemas` with a matching `name`, for dispatching a parsed tool call
+/// against the set of known tools.
+pub fn find_tool_by_name<'a>(schemas: &'a [ToolSchema], name: &str) -> Option<&'a ToolSchema> {
+    schemas.iter().find(|schema| schema.name == name)
+}
+
+/// Emit a constrained grammar over `schemas`: a JSON Schema whose `oneOf` has one alternative per
+/// tool, each pinning `tool_name` to that tool's name (via `const`) and `tool_input` to its own
+/// `input_schema` — the same shape inference servers use to constrain function-call decoding to a
+/// known set of tools and their argument shapes.
+pub fn build_tool_grammar(schemas: &[ToolSchema]) -> String {
+    let alternatives: Vec<Value> = schemas
+        .iter()
+        .map(|schema| {
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "tool_name": { "const": schema.name },
+                    "tool_input": schema.input_schema.clone(),
+                },
+                "required": ["tool_name", "tool_input"],
+            })
+        })
+        .collect();
+
+    let grammar = serde_json::json!({ "oneOf": alternatives });
+    serde_json::to_string_pretty(&grammar).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Parse every tool's `tool_info()` JSON out of the `.py` files in `tools_dir` (skipping
+/// `__init__.py`, same as [`crate::prompts::PromptManager::get_tooluse_prompt`]'s previous raw
+/// dump did), returning both the schemas found and every [`SchemaIssue`] across all of them.
+pub async fn load_tool_schemas(tools_dir: &Path) -> DgmResult<(Vec<ToolSchema>, Vec<SchemaIssue>)> {
+    let mut schemas = Vec::new();
+    let mut issues = Vec::new();
+
+    let mut entries = fs::read_dir(tools_dir).await.context("Failed to read tools directory")?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("py")
+            || path.file_name().and_then(|s| s.to_str()) == Some("__init__.py")
+        {
+            continue;
+        }
+
+        let content = fs::read_to_string(&path).await.with_context(|| format!("Failed to read tool file: {:?}", path))?;
+        let source_file = path.file_name().and_then(|s| s.to_str()).unwrap_or("<unknown>").to_string();
+
+        let Some(info) = extract_tool_info(&content) else {
+            issues.push(SchemaIssue::MissingField {
+                tool: source_file.clone(),
+                field: "tool_info() (couldn't find or parse its return value)".to_string(),
+            });
+            continue;
+        };
+
+        let name = info.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let description = info.get("description").and_then(Value::as_str).unwrap_or_default().to_string();
+        let input_schema = info.get("input_schema").cloned().unwrap_or(Value::Null);
+
+        let schema = ToolSchema { name, description, input_schema, source_file };
+        issues.extend(validate_tool_schema(&schema));
+        schemas.push(schema);
+    }
+
+    Ok((schemas, issues))
+}
+
+/// Pull the dict literal returned by a Python `tool_info()` function out of `source` and parse it
+/// as JSON. Tool authors are instructed (see `coding_agent_summary_polyglot`) to write it as a
+/// JSON-shaped dict, so a light Python-to-JSON normalization (quotes, booleans, `None`) on top of
+/// brace-matched extraction covers the common cases without a full Python parser.
+fn extract_tool_info(source: &str) -> Option<Value> {
+    let def_pos = source.find("def tool_info")?;
+    let brace_start = def_pos + source[def_pos..].find('{')?;
+    let mut depth = 0i32;
+    let mut brace_end = None;
+    for (offset, ch) in source[brace_start..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    brace_end = Some(brace_start + offset);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let brace_end = brace_end?;
+    let literal = &source[brace_start..=brace_end];
+
+    serde_json::from_str(literal).ok().or_else(|| {
+        let normalized = literal.replace("True", "true").replace("False", "false").replace("None", "null").replace('\'', "\"");
+        serde_json::from_str(&normalized).ok()
+    })
+}
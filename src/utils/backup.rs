@@ -0,0 +1,187 @@
+use crate::utils::docker::DockerManager;
+use crate::{DgmResult, Deserialize, Serialize};
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Everything needed to recreate a container: its image, environment, mounts, and working dir.
+///
+/// This is the durable record written alongside a backup so [`restore`](BackupManager::restore)
+/// doesn't need to re-inspect the original (possibly long-gone) container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub container_id: String,
+    pub image: String,
+    pub env_vars: Vec<String>,
+    pub working_dir: Option<String>,
+    pub named_volumes: Vec<String>,
+}
+
+/// Snapshots and restores a container's filesystem and named volumes, for reproducing the exact
+/// state of an agent run.
+pub struct BackupManager {
+    docker: DockerManager,
+}
+
+impl BackupManager {
+    pub fn new() -> DgmResult<Self> {
+        Ok(Self {
+            docker: DockerManager::new()?,
+        })
+    }
+
+    /// Export a container's filesystem, its named volumes, and a manifest into `out_dir`.
+    pub async fn backup_container(&self, container_id: &str, out_dir: &Path) -> DgmResult<()> {
+        tokio::fs::create_dir_all(out_dir)
+            .await
+            .context("Failed to create backup output directory")?;
+
+        let inspect = self.docker.inspect_container(container_id).await?;
+
+        let image = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.image.clone())
+            .unwrap_or_default();
+        let env_vars = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.env.clone())
+            .unwrap_or_default();
+        let working_dir = inspect
+            .config
+            .as_ref()
+            .and_then(|c| c.working_dir.clone());
+
+        let named_volumes = self.named_volumes_of(container_id).await?;
+
+        // Export the container filesystem itself, via the same export endpoint that backs
+        // `copy_from_container`'s tar machinery.
+        let fs_backup_path = out_dir.join("rootfs.tar");
+        self.docker
+            .copy_from_container(container_id, Path::new("/"), &fs_backup_path)
+            .await
+            .context("Failed to export container filesystem")?;
+
+        // Archive each named volume by running a throwaway container that tars the volume path.
+        for volume in &named_volumes {
+            self.backup_volume(volume, out_dir).await?;
+        }
+
+        let manifest = BackupManifest {
+            container_id: container_id.to_string(),
+            image,
+            env_vars,
+            working_dir,
+            named_volumes,
+        };
+
+        let manifest_path = out_dir.join("manifest.json");
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+        tokio::fs::write(&manifest_path, manifest_json)
+            .await
+            .context("Failed to write backup manifest")?;
+
+        info!("Backed up container '{}' to {:?}", container_id, out_dir);
+        Ok(())
+    }
+
+    /// Recreate a container (and its named volumes) from a backup directory.
+    pub async fn restore(&self, backup_dir: &Path) -> DgmResult<String> {
+        let manifest_path = backup_dir.join("manifest.json");
+        let manifest_content = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .context("Failed to read backup manifest")?;
+        let manifest: BackupManifest = serde_json::from_str(&manifest_content)?;
+
+        for volume in &manifest.named_volumes {
+            self.restore_volume(volume, backup_dir).await?;
+        }
+
+        let container_name = format!("restored_{}", manifest.container_id);
+        let container_id = self
+            .docker
+            .create_container(
+                &manifest.image,
+                &container_name,
+                manifest.working_dir.as_deref(),
+                Some(manifest.env_vars.clone()),
+            )
+            .await
+            .context("Failed to recreate container from manifest")?;
+
+        let rootfs_backup = backup_dir.join("rootfs.tar");
+        if rootfs_backup.exists() {
+            self.docker
+                .copy_to_container(&container_id, &rootfs_backup, Path::new("/"))
+                .await
+                .context("Failed to restore container filesystem")?;
+        }
+
+        info!("Restored container '{}' from {:?}", container_id, backup_dir);
+        Ok(container_id)
+    }
+
+    async fn named_volumes_of(&self, container_id: &str) -> DgmResult<Vec<String>> {
+        let inspect = self.docker.inspect_container(container_id).await?;
+        let mounts = inspect.mounts.unwrap_or_default();
+
+        Ok(mounts
+            .into_iter()
+            .filter_map(|m| m.name)
+            .collect())
+    }
+
+    async fn backup_volume(&self, volume_name: &str, out_dir: &Path) -> DgmResult<()> {
+        let helper_name = format!("dgm_backup_helper_{}", volume_name);
+        let helper_id = self
+            .docker
+            .create_container(
+                "busybox:latest",
+                &helper_name,
+                None,
+                None,
+            )
+            .await
+            .context("Failed to create volume backup helper container")?;
+
+        self.docker.start_container(&helper_id).await?;
+
+        let volume_tar: PathBuf = out_dir.join(format!("volume_{}.tar", volume_name));
+        self.docker
+            .copy_from_container(&helper_id, Path::new(&format!("/{}", volume_name)), &volume_tar)
+            .await
+            .context("Failed to stream volume contents out of helper container")?;
+
+        self.docker.stop_container(&helper_id, 5).await.ok();
+        self.docker.remove_container(&helper_id, true).await.ok();
+
+        Ok(())
+    }
+
+    async fn restore_volume(&self, volume_name: &str, backup_dir: &Path) -> DgmResult<()> {
+        let volume_tar = backup_dir.join(format!("volume_{}.tar", volume_name));
+        if !volume_tar.exists() {
+            return Ok(());
+        }
+
+        let helper_name = format!("dgm_restore_helper_{}", volume_name);
+        let helper_id = self
+            .docker
+            .create_container("busybox:latest", &helper_name, None, None)
+            .await
+            .context("Failed to create volume restore helper container")?;
+
+        self.docker.start_container(&helper_id).await?;
+
+        self.docker
+            .copy_to_container(&helper_id, &volume_tar, Path::new(&format!("/{}", volume_name)))
+            .await
+            .context("Failed to stream volume contents into helper container")?;
+
+        self.docker.stop_container(&helper_id, 5).await.ok();
+        self.docker.remove_container(&helper_id, true).await.ok();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::error;
+
+/// Runs a list of items through an async task in fixed-size batches, bounded by a semaphore so
+/// no more than `worker_count` tasks (and therefore containers) are ever in flight at once.
+///
+/// Unlike launching every item as its own `tokio::spawn` up front, batching keeps memory/handle
+/// usage predictable for very large datasets: one batch's containers are created, run, and torn
+/// down before the next batch starts.
+pub struct BatchRunner {
+    worker_count: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl BatchRunner {
+    /// `worker_count` defaults to the available CPU count when `None`.
+    pub fn new(worker_count: Option<usize>) -> Self {
+        let worker_count = worker_count
+            .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+            .max(1);
+
+        Self {
+            worker_count,
+            semaphore: Arc::new(Semaphore::new(worker_count)),
+        }
+    }
+
+    /// The number of items each batch should contain for `total_items` total, given this
+    /// runner's worker count: one item per worker when the dataset is smaller than the worker
+    /// pool (so we don't over-provision), otherwise the input evenly divided across workers.
+    pub fn batch_size(&self, total_items: usize) -> usize {
+        if total_items <= self.worker_count {
+            1
+        } else {
+            (total_items + self.worker_count - 1) / self.worker_count
+        }
+    }
+
+    /// Run `task` over every item in `items`, in batches of [`Self::batch_size`], with up to
+    /// `worker_count` tasks in flight at once. Batches run one after another; within a batch,
+    /// items run concurrently. Results are returned in item order. A task that panics or whose
+    /// `tokio::spawn` fails to join logs an error and is dropped from the results, same as a
+    /// panicking task would be anywhere else in this codebase.
+    pub async fn run<T, F, Fut, R>(&self, items: Vec<T>, task: F) -> Vec<R>
+    where
+        T: Send + 'static,
+        F: Fn(T) -> Fut + Clone + Send + 'static,
+        Fut: std::future::Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let batch_size = self.batch_size(items.len());
+        let mut results = Vec::with_capacity(items.len());
+        let mut remaining = items;
+
+        while !remaining.is_empty() {
+            let take = batch_size.min(remaining.len());
+            let batch: Vec<T> = remaining.drain(0..take).collect();
+
+            let mut handles = Vec::with_capacity(batch.len());
+            for item in batch {
+                let semaphore = self.semaphore.clone();
+                let task = task.clone();
+                handles.push(tokio::spawn(async move {
+                    let _permit = semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("batch semaphore should never be closed");
+                    task(item).await
+                }));
+            }
+
+            for handle in handles {
+                match handle.await {
+                    Ok(result) => results.push(result),
+                    Err(e) => error!("Batched task panicked: {}", e),
+                }
+            }
+        }
+
+        results
+    }
+}
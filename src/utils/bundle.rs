@@ -0,0 +1,153 @@
+use crate::{utils::EvaluationResult, DgmResult};
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Filename a [`write_bundle`] output is conventionally stored under, alongside (or instead of)
+/// a raw `model_patch.diff`.
+pub const BUNDLE_FILENAME: &str = "model_patch.bundle";
+
+const BUNDLE_VERSION: u32 = 1;
+
+/// Pluggable signer for the detached signature a bundle may carry over its digest, so
+/// [`write_signed_bundle`]/[`read_verified_bundle`] aren't tied to one specific signing scheme.
+pub trait BundleSigner {
+    fn sign(&self, digest: &[u8]) -> Vec<u8>;
+    fn verify(&self, digest: &[u8], signature: &[u8]) -> bool;
+}
+
+/// First line of a bundle file. The patch bytes and a canonical-JSON serialization of the
+/// [`EvaluationResult`] follow it back to back, their lengths given here so the bundle stays a
+/// single flat file rather than needing an archive format.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct BundleHeader {
+    version: u32,
+    run_id: String,
+    parent_commit: String,
+    patch_len: usize,
+    metadata_len: usize,
+    digest: String,
+    signature: Option<String>,
+}
+
+fn digest_hex(patch_bytes: &[u8], metadata_bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(patch_bytes);
+    hasher.update(metadata_bytes);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> DgmResult<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        bail!("Invalid hex signature length");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).context("Invalid hex signature"))
+        .collect()
+}
+
+/// Package `result` and the patch at `patch_path` into a single content-addressed bundle file at
+/// `out`, so a self-improvement run can be archived or copied to another machine and re-imported
+/// into an [`Archive`](crate::dgm::Archive) without trusting its original directory layout.
+pub fn write_bundle(result: &EvaluationResult, patch_path: &Path, out: &Path) -> DgmResult<()> {
+    write_signed_bundle(result, patch_path, out, None)
+}
+
+/// As [`write_bundle`], but also attaches a detached signature over the digest via `signer`.
+pub fn write_signed_bundle(
+    result: &EvaluationResult,
+    patch_path: &Path,
+    out: &Path,
+    signer: Option<&dyn BundleSigner>,
+) -> DgmResult<()> {
+    let patch_bytes = fs::read(patch_path)
+        .with_context(|| format!("Failed to read patch at {:?}", patch_path))?;
+    let metadata_bytes =
+        serde_json::to_vec(result).context("Failed to serialize evaluation result")?;
+
+    let digest = digest_hex(&patch_bytes, &metadata_bytes);
+    let signature = signer.map(|s| hex_encode(&s.sign(digest.as_bytes())));
+
+    let header = BundleHeader {
+        version: BUNDLE_VERSION,
+        run_id: result.run_id.clone(),
+        parent_commit: result.parent_commit.clone(),
+        patch_len: patch_bytes.len(),
+        metadata_len: metadata_bytes.len(),
+        digest,
+        signature,
+    };
+
+    let mut out_bytes =
+        serde_json::to_vec(&header).context("Failed to serialize bundle header")?;
+    out_bytes.push(b'\n');
+    out_bytes.extend_from_slice(&patch_bytes);
+    out_bytes.extend_from_slice(&metadata_bytes);
+
+    fs::write(out, out_bytes).with_context(|| format!("Failed to write bundle to {:?}", out))?;
+    Ok(())
+}
+
+/// Read and verify a bundle written by [`write_bundle`], returning `(metadata, patch)`. Rejects
+/// the bundle if the recomputed digest doesn't match the header, i.e. the patch or metadata were
+/// tampered with or truncated after writing.
+pub fn read_bundle(path: &Path) -> DgmResult<(EvaluationResult, String)> {
+    read_verified_bundle(path, None)
+}
+
+/// As [`read_bundle`], but also verifies the bundle's detached signature (if it has one) via
+/// `verifier`. A signed bundle read without a `verifier` is rejected rather than silently
+/// accepted unverified.
+pub fn read_verified_bundle(
+    path: &Path,
+    verifier: Option<&dyn BundleSigner>,
+) -> DgmResult<(EvaluationResult, String)> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read bundle at {:?}", path))?;
+    let newline = bytes
+        .iter()
+        .position(|&b| b == b'\n')
+        .context("Bundle is missing its header line")?;
+    let header: BundleHeader = serde_json::from_slice(&bytes[..newline])
+        .context("Failed to parse bundle header")?;
+
+    let body = &bytes[newline + 1..];
+    if body.len() != header.patch_len + header.metadata_len {
+        bail!("Bundle body length does not match its header (truncated or corrupted bundle)");
+    }
+    let patch_bytes = &body[..header.patch_len];
+    let metadata_bytes = &body[header.patch_len..];
+
+    let digest = digest_hex(patch_bytes, metadata_bytes);
+    if digest != header.digest {
+        bail!(
+            "Bundle digest mismatch: header says {}, computed {} (tampered or corrupted bundle)",
+            header.digest,
+            digest
+        );
+    }
+
+    if let Some(signature_hex) = &header.signature {
+        let verifier = verifier.context("Bundle carries a signature but no verifier was given")?;
+        let signature = hex_decode(signature_hex)?;
+        if !verifier.verify(header.digest.as_bytes(), &signature) {
+            bail!("Bundle signature failed verification");
+        }
+    }
+
+    let metadata: EvaluationResult =
+        serde_json::from_slice(metadata_bytes).context("Failed to parse bundle metadata")?;
+    let patch = String::from_utf8(patch_bytes.to_vec())
+        .context("Bundle patch is not valid UTF-8")?;
+
+    Ok((metadata, patch))
+}
@@ -0,0 +1,130 @@
+use regex::Regex;
+use std::fmt;
+
+/// Python constructs the "code as action" tool-use strategy (see
+/// [`crate::prompts::PromptManager::get_code_action_prompt`]) refuses to run, mirroring the
+/// warnings `coding_agent_summary`/`coding_agent_summary_polyglot` already give a human author of
+/// agent code.
+const DISALLOWED_CONSTRUCTS: &[(&str, &str)] = &[(r"while\s+True\s*:", "while True: loop")];
+
+/// A problem found in a model-authored code-action snippet by [`validate_code_action`], instead of
+/// handing it straight to the harness to execute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeActionIssue {
+    /// Parens/brackets/braces (or quotes) don't balance — the snippet isn't valid Python.
+    UnbalancedDelimiters { expected: char, found_at_line: usize },
+    UnterminatedString { line: usize },
+    /// The snippet uses a construct this strategy disallows, e.g. `while True:`.
+    DisallowedConstruct { construct: String, line: usize },
+}
+
+impl fmt::Display for CodeActionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnbalancedDelimiters { expected, found_at_line } if matches!(expected, '(' | '[' | '{') => {
+                write!(f, "line {found_at_line}: unbalanced delimiters (missing an opening '{expected}')")
+            }
+            Self::UnbalancedDelimiters { expected, found_at_line } => {
+                write!(f, "line {found_at_line}: unbalanced delimiters (missing a closing '{expected}')")
+            }
+            Self::UnterminatedString { line } => write!(f, "line {line}: unterminated string literal"),
+            Self::DisallowedConstruct { construct, line } => write!(f, "line {line}: disallowed construct '{construct}'"),
+        }
+    }
+}
+
+/// Check a code-action snippet for balanced delimiters/strings and disallowed constructs before
+/// the harness executes it. This is a light, regex/bracket-counting pass rather than a real Python
+/// parser (this crate doesn't vendor one) — it catches the common failure modes (a snippet cut off
+/// mid-generation, a forbidden `while True:`) without claiming to validate full Python grammar.
+pub fn validate_code_action(code: &str) -> Vec<CodeActionIssue> {
+    let mut issues = Vec::new();
+    issues.extend(check_balanced_delimiters(code));
+    issues.extend(check_disallowed_constructs(code));
+    issues
+}
+
+fn check_balanced_delimiters(code: &str) -> Vec<CodeActionIssue> {
+    let mut issues = Vec::new();
+    let mut stack: Vec<(char, usize)> = Vec::new();
+    let mut line = 1usize;
+    let mut in_string: Option<char> = None;
+    let mut chars = code.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\n' {
+            line += 1;
+        }
+
+        if let Some(quote) = in_string {
+            if ch == '\\' {
+                chars.next();
+            } else if ch == quote {
+                in_string = None;
+            }
+            continue;
+        }
+
+        match ch {
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '\'' | '"' => in_string = Some(ch),
+            '(' | '[' | '{' => stack.push((ch, line)),
+            ')' | ']' | '}' => {
+                let expected = match ch {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                match stack.pop() {
+                    Some((open, _)) if open == expected => {}
+                    // An opener closed by the wrong kind is still missing its own closer.
+                    Some((open, _)) => {
+                        let closer = match open {
+                            '(' => ')',
+                            '[' => ']',
+                            _ => '}',
+                        };
+                        issues.push(CodeActionIssue::UnbalancedDelimiters { expected: closer, found_at_line: line });
+                    }
+                    // A closer with nothing open is missing its opener.
+                    None => issues.push(CodeActionIssue::UnbalancedDelimiters { expected, found_at_line: line }),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(_quote) = in_string {
+        issues.push(CodeActionIssue::UnterminatedString { line });
+    }
+    for (open, opened_at_line) in stack {
+        let expected = match open {
+            '(' => ')',
+            '[' => ']',
+            _ => '}',
+        };
+        issues.push(CodeActionIssue::UnbalancedDelimiters { expected, found_at_line: opened_at_line });
+    }
+
+    issues
+}
+
+fn check_disallowed_constructs(code: &str) -> Vec<CodeActionIssue> {
+    let mut issues = Vec::new();
+    for (pattern, label) in DISALLOWED_CONSTRUCTS {
+        let Ok(regex) = Regex::new(pattern) else { continue };
+        for (line_number, line) in code.lines().enumerate() {
+            if regex.is_match(line) {
+                issues.push(CodeActionIssue::DisallowedConstruct { construct: (*label).to_string(), line: line_number + 1 });
+            }
+        }
+    }
+    issues
+}
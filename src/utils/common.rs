@@ -55,6 +55,17 @@ pub fn extract_json_between_markers(text: &str) -> Option<Value> {
     None
 }
 
+/// Extract a fenced Python code block from text, analogous to [`extract_json_between_markers`].
+/// Used by the "code as action" tool-use strategy (see
+/// [`crate::prompts::PromptManager::get_code_action_prompt`]) to pull the snippet the model wrote
+/// out of its response.
+pub fn extract_code_between_markers(text: &str) -> Option<String> {
+    let start = text.find("```python")?;
+    let body_start = start + "```python".len();
+    let end = text[body_start..].find("```")?;
+    Some(text[body_start..body_start + end].trim().to_string())
+}
+
 /// Create directory if it doesn't exist
 pub fn ensure_dir_exists<P: AsRef<Path>>(path: P) -> DgmResult<()> {
     fs::create_dir_all(path.as_ref())?;
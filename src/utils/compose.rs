@@ -0,0 +1,196 @@
+use crate::utils::docker::DockerManager;
+use crate::DgmResult;
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// A single service definition parsed from a `docker-compose.yml` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeService {
+    pub image: Option<String>,
+    pub build: Option<String>,
+    #[serde(default)]
+    pub environment: Vec<String>,
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub networks: Vec<String>,
+    #[serde(default)]
+    pub volumes: Vec<String>,
+}
+
+/// Top-level shape of a `docker-compose.yml` file (the subset DGM understands).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeFile {
+    pub services: HashMap<String, ComposeService>,
+}
+
+/// Orchestrates a set of services described by a compose file on top of [`DockerManager`].
+///
+/// This lets DGM bring up a code-under-test container plus its backing services (a database, a
+/// cache) as one unit instead of hand-wiring single containers.
+pub struct ComposeManager {
+    docker: DockerManager,
+    project_name: String,
+}
+
+/// Running state for a brought-up compose stack, needed to tear it down again.
+pub struct ComposeStack {
+    pub network_name: String,
+    pub container_ids: Vec<(String, String)>, // (service name, container id), in start order
+}
+
+impl ComposeManager {
+    pub fn new(project_name: &str) -> DgmResult<Self> {
+        Ok(Self {
+            docker: DockerManager::new()?,
+            project_name: project_name.to_string(),
+        })
+    }
+
+    /// Parse a compose file and bring its services up in dependency order.
+    ///
+    /// Creates a dedicated user-defined bridge network for the project, builds or pulls each
+    /// service's image, then starts containers in the order given by a topological sort over
+    /// `depends_on`.
+    pub async fn compose_up(&self, path: &Path) -> DgmResult<ComposeStack> {
+        let compose = self.parse_compose_file(path).context("Failed to parse compose file")?;
+        let order = Self::topological_order(&compose.services)?;
+
+        let network_name = format!("{}_default", self.project_name);
+        self.docker.create_network(&network_name).await?;
+
+        let mut container_ids = Vec::new();
+        for service_name in &order {
+            let service = &compose.services[service_name];
+            let image_name = self.resolve_image(service_name, service, path).await?;
+
+            let container_name = format!("{}_{}", self.project_name, service_name);
+            let env_vars = if service.environment.is_empty() {
+                None
+            } else {
+                Some(service.environment.clone())
+            };
+
+            let container_id = self
+                .docker
+                .create_container(
+                    &image_name,
+                    &container_name,
+                    service.working_dir.as_deref(),
+                    env_vars,
+                )
+                .await
+                .with_context(|| format!("Failed to create container for service '{}'", service_name))?;
+
+            self.docker.connect_to_network(&container_id, &network_name).await?;
+            self.docker.start_container(&container_id).await?;
+
+            info!("Service '{}' up as container '{}'", service_name, container_id);
+            container_ids.push((service_name.clone(), container_id));
+        }
+
+        Ok(ComposeStack {
+            network_name,
+            container_ids,
+        })
+    }
+
+    /// Stop and remove all containers in the stack plus its network, in reverse start order.
+    pub async fn compose_down(&self, stack: &ComposeStack) -> DgmResult<()> {
+        for (service_name, container_id) in stack.container_ids.iter().rev() {
+            if let Err(e) = self.docker.stop_container(container_id, 10).await {
+                warn!("Failed to stop service '{}': {}", service_name, e);
+            }
+            if let Err(e) = self.docker.remove_container(container_id, true).await {
+                warn!("Failed to remove service '{}': {}", service_name, e);
+            }
+        }
+
+        self.docker.remove_network(&stack.network_name).await?;
+        Ok(())
+    }
+
+    fn parse_compose_file(&self, path: &Path) -> DgmResult<ComposeFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read compose file at {:?}", path))?;
+        let compose: ComposeFile =
+            serde_yaml::from_str(&content).context("Failed to parse compose YAML")?;
+        Ok(compose)
+    }
+
+    async fn resolve_image(
+        &self,
+        service_name: &str,
+        service: &ComposeService,
+        compose_path: &Path,
+    ) -> DgmResult<String> {
+        if let Some(image) = &service.image {
+            self.docker.ensure_image(image).await?;
+            return Ok(image.clone());
+        }
+
+        if let Some(build_dir) = &service.build {
+            let dockerfile_dir = compose_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(build_dir);
+            let dockerfile_path = dockerfile_dir.join("Dockerfile");
+            let image_name = format!("{}_{}", self.project_name, service_name);
+            return self.docker.build_image(&dockerfile_path, &image_name, false).await;
+        }
+
+        Err(anyhow!(
+            "Service '{}' has neither 'image' nor 'build' set",
+            service_name
+        )
+        .into())
+    }
+
+    /// Topologically sort services by `depends_on` so dependencies start first.
+    fn topological_order(services: &HashMap<String, ComposeService>) -> DgmResult<Vec<String>> {
+        let mut visited = HashSet::new();
+        let mut in_progress = HashSet::new();
+        let mut order = Vec::new();
+
+        fn visit(
+            name: &str,
+            services: &HashMap<String, ComposeService>,
+            visited: &mut HashSet<String>,
+            in_progress: &mut HashSet<String>,
+            order: &mut Vec<String>,
+        ) -> DgmResult<()> {
+            if visited.contains(name) {
+                return Ok(());
+            }
+            if in_progress.contains(name) {
+                return Err(anyhow!("Circular depends_on detected involving service '{}'", name).into());
+            }
+
+            let service = services
+                .get(name)
+                .ok_or_else(|| anyhow!("Unknown service referenced in depends_on: '{}'", name))?;
+
+            in_progress.insert(name.to_string());
+            for dep in &service.depends_on {
+                visit(dep, services, visited, in_progress, order)?;
+            }
+            in_progress.remove(name);
+
+            visited.insert(name.to_string());
+            order.push(name.to_string());
+            Ok(())
+        }
+
+        let mut names: Vec<&String> = services.keys().collect();
+        names.sort();
+        for name in names {
+            visit(name, services, &mut visited, &mut in_progress, &mut order)?;
+        }
+
+        Ok(order)
+    }
+}
@@ -0,0 +1,196 @@
+//! Line-based unified diff formatting, shared by anything that needs to show a human-reviewable
+//! record of a text mutation rather than a bare success/failure message.
+
+/// One hunk of a unified diff: a contiguous span of changed lines plus its surrounding context,
+/// in the `@@ -old_start,old_len +new_start,new_len @@` format.
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    /// Each line already prefixed with `' '` (context), `'-'` (removed), or `'+'` (added).
+    pub lines: Vec<String>,
+}
+
+impl DiffHunk {
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        );
+        for line in &self.lines {
+            out.push('\n');
+            out.push_str(line);
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LineOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Longest-common-subsequence edit script between `a` and `b`. `O(a.len() * b.len())` time and
+/// space, which is fine for the file-sized inputs this backs (a single `EditTool` mutation), not
+/// intended for diffing arbitrarily large trees.
+fn lcs_edit_script(a: &[&str], b: &[&str]) -> Vec<LineOp> {
+    let n = a.len();
+    let m = b.len();
+    let mut dp = vec![vec![0u32; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if a[i] == b[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            ops.push(LineOp::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            ops.push(LineOp::Delete(i));
+            i += 1;
+        } else {
+            ops.push(LineOp::Insert(j));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push(LineOp::Delete(i));
+        i += 1;
+    }
+    while j < m {
+        ops.push(LineOp::Insert(j));
+        j += 1;
+    }
+
+    ops
+}
+
+/// Group an edit script into hunks, padding each change with up to `context` lines of unchanged
+/// context on either side and merging runs of changes separated by no more than `2 * context`
+/// unchanged lines into a single hunk.
+fn group_into_hunks(ops: &[LineOp], context: usize) -> Vec<Vec<LineOp>> {
+    let is_equal = |op: &LineOp| matches!(op, LineOp::Equal(_, _));
+
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < ops.len() {
+        if is_equal(&ops[i]) {
+            i += 1;
+            continue;
+        }
+
+        let start = i.saturating_sub(context);
+        let mut end = i;
+
+        loop {
+            while end < ops.len() && !is_equal(&ops[end]) {
+                end += 1;
+            }
+
+            let gap_start = end;
+            let mut gap_end = end;
+            while gap_end < ops.len() && is_equal(&ops[gap_end]) && gap_end - gap_start < 2 * context {
+                gap_end += 1;
+            }
+
+            if gap_end < ops.len() && !is_equal(&ops[gap_end]) {
+                end = gap_end;
+            } else {
+                end = (gap_start + context).min(ops.len());
+                break;
+            }
+        }
+
+        groups.push(ops[start..end].to_vec());
+        i = end;
+    }
+
+    groups
+}
+
+fn render_group(group: &[LineOp], a: &[&str], b: &[&str]) -> DiffHunk {
+    let old_start = group
+        .iter()
+        .find_map(|op| match op {
+            LineOp::Equal(o, _) | LineOp::Delete(o) => Some(*o),
+            LineOp::Insert(_) => None,
+        })
+        .unwrap_or(0);
+    let new_start = group
+        .iter()
+        .find_map(|op| match op {
+            LineOp::Equal(_, n) | LineOp::Insert(n) => Some(*n),
+            LineOp::Delete(_) => None,
+        })
+        .unwrap_or(0);
+
+    let mut lines = Vec::with_capacity(group.len());
+    let mut old_len = 0;
+    let mut new_len = 0;
+
+    for op in group {
+        match op {
+            LineOp::Equal(o, _) => {
+                lines.push(format!(" {}", a[*o]));
+                old_len += 1;
+                new_len += 1;
+            }
+            LineOp::Delete(o) => {
+                lines.push(format!("-{}", a[*o]));
+                old_len += 1;
+            }
+            LineOp::Insert(n) => {
+                lines.push(format!("+{}", b[*n]));
+                new_len += 1;
+            }
+        }
+    }
+
+    // Unified-diff convention: a side that contributes zero lines (a pure insertion's "old" side,
+    // a pure deletion's "new" side, or a brand-new/fully-deleted file) reports start `0`, not the
+    // 1-indexed position of a line that doesn't exist.
+    DiffHunk {
+        old_start: if old_len == 0 { 0 } else { old_start + 1 },
+        old_len,
+        new_start: if new_len == 0 { 0 } else { new_start + 1 },
+        new_len,
+        lines,
+    }
+}
+
+/// Compute the unified-diff hunks between `old` and `new`, with `context` lines of unchanged
+/// context padding each hunk.
+pub fn diff_hunks(old: &str, new: &str, context: usize) -> Vec<DiffHunk> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    let ops = lcs_edit_script(&a, &b);
+    group_into_hunks(&ops, context)
+        .iter()
+        .map(|group| render_group(group, &a, &b))
+        .collect()
+}
+
+/// Render the unified diff between `old` and `new` as a single string (hunks only, no `---`/`+++`
+/// file headers — callers that have a path to attach can prepend their own).
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    diff_hunks(old, new, context)
+        .iter()
+        .map(|hunk| hunk.render())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -5,38 +5,346 @@ use bollard::models::{ContainerCreateBody, ContainerInspectResponse, HostConfig}
 use bollard::query_parameters::{
     BuildImageOptions, CreateContainerOptions, RemoveContainerOptions, RemoveImageOptions,
     StartContainerOptions, StopContainerOptions, UploadToContainerOptions,
-    InspectContainerOptions, DownloadFromContainerOptions,
+    InspectContainerOptions, DownloadFromContainerOptions, CreateNetworkOptions,
+    RemoveNetworkOptions, InspectNetworkOptions,
 };
+use bollard::models::NetworkConnectRequest;
 use bollard::Docker;
 use bytes::Bytes;
-use futures::stream::StreamExt;
+use futures::stream::{self, Stream, StreamExt};
 use http_body_util::{Either, Full};
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::time::Duration;
 use tar::Builder;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 
+/// Prefix marking a line of container output as a parsed progress/state update rather than
+/// plain stdout/stderr chatter, e.g. `::dgm-progress:: applying patch 2/5`. The coding agent
+/// scripts emit these to give long runs a cheap way to surface milestones without the consumer
+/// having to parse arbitrary log text.
+const PROGRESS_MARKER_PREFIX: &str = "::dgm-progress::";
+
+/// The channel a line of exec output came from, or a parsed progress/state marker pulled out of
+/// either channel (see [`PROGRESS_MARKER_PREFIX`]).
+#[derive(Debug, Clone)]
+pub enum LogChannel {
+    Stdout(String),
+    Stderr(String),
+    Progress(String),
+}
+
+/// A single line of output produced while streaming an exec session, tagged by channel and
+/// timestamped at the moment it was read so a consumer can reconstruct timing even if it only
+/// persists the lines (e.g. to a per-instance log file) rather than watching them live.
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub channel: LogChannel,
+}
+
+impl LogLine {
+    fn new(channel: LogChannel) -> Self {
+        Self {
+            timestamp: chrono::Utc::now(),
+            channel,
+        }
+    }
+
+    /// The line's text, regardless of which channel it came from.
+    pub fn text(&self) -> &str {
+        match &self.channel {
+            LogChannel::Stdout(text) | LogChannel::Stderr(text) | LogChannel::Progress(text) => text,
+        }
+    }
+}
+
+/// Line-buffering adapter that accumulates raw bytes per channel and emits complete lines.
+///
+/// Bytes are split on `\n` as they arrive; any trailing partial line is held until either a
+/// newline completes it or `flush` is called at stream end.
+#[derive(Default)]
+struct LineBuffer {
+    stdout_buf: Vec<u8>,
+    stderr_buf: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push_stdout(&mut self, bytes: &[u8], out: &mut Vec<LogLine>) {
+        Self::push(&mut self.stdout_buf, bytes, out, LogChannel::Stdout);
+    }
+
+    fn push_stderr(&mut self, bytes: &[u8], out: &mut Vec<LogLine>) {
+        Self::push(&mut self.stderr_buf, bytes, out, LogChannel::Stderr);
+    }
+
+    fn push(buf: &mut Vec<u8>, bytes: &[u8], out: &mut Vec<LogLine>, wrap: fn(String) -> LogChannel) {
+        buf.extend_from_slice(bytes);
+        while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line[..line.len() - 1]).to_string();
+            out.push(LogLine::new(Self::classify(line, wrap)));
+        }
+    }
+
+    /// Flush any trailing partial lines once the underlying stream has ended.
+    fn flush(mut self, out: &mut Vec<LogLine>) {
+        if !self.stdout_buf.is_empty() {
+            let line = String::from_utf8_lossy(&self.stdout_buf).to_string();
+            out.push(LogLine::new(Self::classify(line, LogChannel::Stdout)));
+            self.stdout_buf.clear();
+        }
+        if !self.stderr_buf.is_empty() {
+            let line = String::from_utf8_lossy(&self.stderr_buf).to_string();
+            out.push(LogLine::new(Self::classify(line, LogChannel::Stderr)));
+            self.stderr_buf.clear();
+        }
+    }
+
+    /// Pull a [`PROGRESS_MARKER_PREFIX`]-tagged line out into `LogChannel::Progress` regardless
+    /// of which channel it arrived on; otherwise wrap it as the channel it actually came from.
+    fn classify(line: String, wrap: fn(String) -> LogChannel) -> LogChannel {
+        match line.strip_prefix(PROGRESS_MARKER_PREFIX) {
+            Some(rest) => LogChannel::Progress(rest.trim_start().to_string()),
+            None => wrap(line),
+        }
+    }
+}
+
+/// Resource limits and networking to apply to a container created via
+/// [`DockerManager::create_container_with_spec`].
+///
+/// This exists so agent-spawned containers can be bounded (CPU/memory/pids) and placed on a
+/// shared network, which is essential for safely running untrusted evolved code. Any field left
+/// unset falls back to Docker's own default (effectively unlimited), matching today's behavior.
+#[derive(Debug, Clone, Default)]
+pub struct ContainerSpec {
+    pub working_dir: Option<String>,
+    pub env_vars: Option<Vec<String>>,
+    /// Memory limit in bytes.
+    pub memory_limit_bytes: Option<u64>,
+    /// CPU quota in microseconds per 100ms period (see Docker's `--cpu-quota`).
+    pub cpu_quota: Option<u64>,
+    pub cpu_shares: Option<u64>,
+    pub pids_limit: Option<u64>,
+    /// e.g. "bridge", "host", or a custom network name.
+    pub network_mode: Option<String>,
+    /// Maps container port (e.g. "8080/tcp") to a host port to publish it on.
+    pub port_bindings: std::collections::HashMap<String, u16>,
+    /// Maps host path to container path for bind-mounted volumes.
+    pub volume_mounts: Vec<(String, String)>,
+    pub read_only_rootfs: Option<bool>,
+}
+
+/// Parsed `.dockerignore` patterns, with support for `!`-prefixed negation the way the Docker
+/// daemon itself interprets them: later rules override earlier ones.
+struct DockerIgnore {
+    /// (glob pattern, is_negation)
+    rules: Vec<(glob::Pattern, bool)>,
+}
+
+impl DockerIgnore {
+    fn load(context_dir: &Path, extra_patterns: &[&str]) -> DgmResult<Self> {
+        let mut rules = Vec::new();
+
+        let dockerignore_path = context_dir.join(".dockerignore");
+        if dockerignore_path.is_file() {
+            let content = std::fs::read_to_string(&dockerignore_path)
+                .context("Failed to read .dockerignore")?;
+            for line in content.lines() {
+                Self::push_rule(&mut rules, line)?;
+            }
+        }
+
+        for pattern in extra_patterns {
+            Self::push_rule(&mut rules, pattern)?;
+        }
+
+        Ok(Self { rules })
+    }
+
+    fn push_rule(rules: &mut Vec<(glob::Pattern, bool)>, line: &str) -> DgmResult<()> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        let (negate, raw_pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        // Treat "dir" as "dir/**" so it matches everything beneath it too.
+        let pattern_str = format!("{}/**", raw_pattern.trim_end_matches('/'));
+        let pattern = glob::Pattern::new(&pattern_str).context("Invalid .dockerignore pattern")?;
+        rules.push((pattern, negate));
+
+        let exact_pattern = glob::Pattern::new(raw_pattern).context("Invalid .dockerignore pattern")?;
+        rules.push((exact_pattern, negate));
+
+        Ok(())
+    }
+
+    fn is_ignored(&self, relative_path: &Path) -> bool {
+        let path_str = relative_path.to_string_lossy();
+        let mut ignored = false;
+        for (pattern, negate) in &self.rules {
+            if pattern.matches(&path_str) {
+                ignored = !negate;
+            }
+        }
+        ignored
+    }
+}
+
+/// Strategy for deciding when a container is ready for use, for
+/// [`DockerManager::wait_until_ready`].
+pub enum ReadyStrategy {
+    /// Poll the container's inspected `State.Health` until it reports healthy.
+    Healthcheck,
+    /// Exec this command in the container on each poll; ready once it exits 0.
+    ReadinessCommand(Vec<String>),
+    /// Ready once this regex matches anywhere in the container's combined stdout/stderr logs.
+    LogPattern(regex::Regex),
+}
+
 /// Docker client wrapper for container management
 pub struct DockerManager {
     client: Docker,
+    /// Serializes `ensure_image` so concurrent callers never race to pull/build the same image
+    /// onto this daemon twice.
+    image_lock: tokio::sync::Mutex<()>,
+}
+
+/// TLS material for connecting to a remote Docker daemon over `tcp://`.
+#[derive(Debug, Clone)]
+pub struct DockerTlsConfig {
+    pub ca_cert_path: PathBuf,
+    pub client_cert_path: PathBuf,
+    pub client_key_path: PathBuf,
+}
+
+/// Where to find the Docker daemon `DockerManager` should talk to.
+#[derive(Debug, Clone)]
+pub enum DockerEndpoint {
+    /// The local Unix socket / named pipe (`connect_with_local_defaults`).
+    Local,
+    /// A remote daemon over plain TCP, e.g. `tcp://build-host:2375`.
+    Tcp { address: String },
+    /// A remote daemon over TLS-secured TCP, e.g. `tcp://build-host:2376`.
+    TcpTls { address: String, tls: DockerTlsConfig },
+    /// Derive the endpoint from the `DOCKER_HOST` (and `DOCKER_TLS_VERIFY`/`DOCKER_CERT_PATH`)
+    /// environment variables, the same way the `docker` CLI does.
+    FromEnv,
+}
+
+impl Default for DockerEndpoint {
+    fn default() -> Self {
+        DockerEndpoint::Local
+    }
 }
 
 impl DockerManager {
-    /// Create a new Docker manager instance
+    /// Create a new Docker manager instance connected to the local daemon.
     pub fn new() -> DgmResult<Self> {
         let client = Docker::connect_with_local_defaults()
             .context("Failed to connect to Docker daemon")?;
-        Ok(Self { client })
+        Ok(Self { client, image_lock: tokio::sync::Mutex::new(()) })
+    }
+
+    /// Connect to an arbitrary endpoint — local socket, remote TCP, or TLS-secured remote TCP —
+    /// pinning a minimum supported Docker API version so we fail fast against a daemon that's
+    /// too old to support the operations DGM relies on.
+    pub fn connect(endpoint: DockerEndpoint, min_api_version: Option<&str>) -> DgmResult<Self> {
+        let client = match endpoint {
+            DockerEndpoint::Local => Docker::connect_with_local_defaults()
+                .context("Failed to connect to local Docker daemon")?,
+            DockerEndpoint::Tcp { address } => {
+                Docker::connect_with_http(&address, 120, bollard::API_DEFAULT_VERSION)
+                    .with_context(|| format!("Failed to connect to Docker daemon at {}", address))?
+            }
+            DockerEndpoint::TcpTls { address, tls } => Docker::connect_with_ssl(
+                &address,
+                &tls.client_key_path,
+                &tls.client_cert_path,
+                &tls.ca_cert_path,
+                120,
+                bollard::API_DEFAULT_VERSION,
+            )
+            .with_context(|| format!("Failed to connect to TLS Docker daemon at {}", address))?,
+            DockerEndpoint::FromEnv => Docker::connect_with_defaults()
+                .context("Failed to connect to Docker daemon from DOCKER_HOST environment")?,
+        };
+
+        if let Some(min_version) = min_api_version {
+            Self::check_api_version(&client, min_version)?;
+        }
+
+        Ok(Self { client, image_lock: tokio::sync::Mutex::new(()) })
+    }
+
+    /// The Docker daemon's negotiated API version (e.g. "1.45"). Exposed so callers can preflight
+    /// a whole fleet of endpoints against their own acceptable-version policy, beyond the single
+    /// `min_api_version` bound enforced once at [`connect`](Self::connect) time.
+    pub fn api_version(&self) -> &str {
+        self.client.client_version()
+    }
+
+    /// Error early if the daemon's negotiated API version is older than `min_version`
+    /// (e.g. "1.41"), following butido's `required_docker_api_versions` pattern.
+    fn check_api_version(client: &Docker, min_version: &str) -> DgmResult<()> {
+        let min_parts = Self::parse_version(min_version)?;
+        let negotiated = client.client_version();
+        let negotiated_parts = Self::parse_version(negotiated)?;
+
+        if negotiated_parts < min_parts {
+            return Err(anyhow!(
+                "Docker daemon API version {} is older than the minimum required version {}",
+                negotiated,
+                min_version
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn parse_version(version: &str) -> DgmResult<(u32, u32)> {
+        let mut parts = version.split('.');
+        let major: u32 = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(|| anyhow!("Invalid Docker API version string: {}", version))?;
+        let minor: u32 = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(|| anyhow!("Invalid Docker API version string: {}", version))?;
+        Ok((major, minor))
     }
 
-    /// Build a Docker image from a Dockerfile
+    /// Build a Docker image from a Dockerfile, respecting `.dockerignore` in the context root.
+    ///
+    /// `extra_ignore_patterns` are added on top of whatever `.dockerignore` specifies (or used
+    /// alone if no `.dockerignore` is present), using the same glob/negation semantics.
     pub async fn build_image(
         &self,
         dockerfile_path: &Path,
         image_name: &str,
         force_rebuild: bool,
+    ) -> DgmResult<String> {
+        self.build_image_with_ignores(dockerfile_path, image_name, force_rebuild, &[])
+            .await
+    }
+
+    /// Like [`build_image`](Self::build_image), with additional `.dockerignore`-style patterns.
+    pub async fn build_image_with_ignores(
+        &self,
+        dockerfile_path: &Path,
+        image_name: &str,
+        force_rebuild: bool,
+        extra_ignore_patterns: &[&str],
     ) -> DgmResult<String> {
         // Check if image already exists
         if !force_rebuild {
@@ -52,8 +360,10 @@ impl DockerManager {
             .parent()
             .ok_or_else(|| anyhow!("Invalid dockerfile path"))?;
 
-        // Create build context tar
-        let build_context = self.create_build_context(dockerfile_dir).await?;
+        // Create a gzip-compressed build context tar, skipping .dockerignore-matched paths
+        let build_context = self
+            .create_build_context(dockerfile_dir, extra_ignore_patterns)
+            .await?;
 
         let options = BuildImageOptions {
             dockerfile: dockerfile_path
@@ -87,52 +397,131 @@ impl DockerManager {
         Ok(image_name.to_string())
     }
 
-    /// Create a build context tar archive from a directory
-    async fn create_build_context(&self, context_dir: &Path) -> DgmResult<Vec<u8>> {
+    /// Create a gzip-compressed build context tar archive from a directory, skipping any path
+    /// matched by `.dockerignore` (if present) or `extra_ignore_patterns`.
+    async fn create_build_context(
+        &self,
+        context_dir: &Path,
+        extra_ignore_patterns: &[&str],
+    ) -> DgmResult<Vec<u8>> {
+        let ignore = DockerIgnore::load(context_dir, extra_ignore_patterns)?;
+
         let mut tar_data = Vec::new();
         {
             let mut tar = Builder::new(&mut tar_data);
 
-            // Add all files in the context directory
             for entry in walkdir::WalkDir::new(context_dir) {
                 let entry = entry.context("Failed to read directory entry")?;
                 let path = entry.path();
 
-                if path.is_file() {
-                    let relative_path = path
-                        .strip_prefix(context_dir)
-                        .context("Failed to create relative path")?;
+                if !path.is_file() {
+                    continue;
+                }
+
+                let relative_path = path
+                    .strip_prefix(context_dir)
+                    .context("Failed to create relative path")?;
 
-                    tar.append_path_with_name(path, relative_path)
-                        .context("Failed to add file to tar")?;
+                if ignore.is_ignored(relative_path) {
+                    debug!("Skipping ignored build context path: {:?}", relative_path);
+                    continue;
                 }
+
+                tar.append_path_with_name(path, relative_path)
+                    .context("Failed to add file to tar")?;
             }
 
             tar.finish().context("Failed to finalize tar archive")?;
         }
 
-        Ok(tar_data)
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &tar_data)
+            .context("Failed to gzip build context")?;
+        encoder.finish().context("Failed to finalize gzip stream")
     }
 
-    /// Create and start a container from an image
+    /// Create (but do not start) a container from an image, using today's default resource limits
+    /// (none) and bridge networking. See [`create_container_with_spec`](Self::create_container_with_spec)
+    /// to constrain CPU/memory/network for untrusted evolved code.
     pub async fn create_container(
         &self,
         image_name: &str,
         container_name: &str,
         working_dir: Option<&str>,
         env_vars: Option<Vec<String>>,
+    ) -> DgmResult<String> {
+        let spec = ContainerSpec {
+            working_dir: working_dir.map(|s| s.to_string()),
+            env_vars,
+            ..ContainerSpec::default()
+        };
+        self.create_container_with_spec(image_name, container_name, &spec).await
+    }
+
+    /// Create (but do not start) a container from an image with explicit resource and network
+    /// limits. See [`start_container`](Self::start_container).
+    pub async fn create_container_with_spec(
+        &self,
+        image_name: &str,
+        container_name: &str,
+        spec: &ContainerSpec,
     ) -> DgmResult<String> {
         // Remove existing container with the same name if it exists
         self.remove_existing_container(container_name).await?;
 
+        let port_bindings = if spec.port_bindings.is_empty() {
+            None
+        } else {
+            let mut map = std::collections::HashMap::new();
+            for (container_port, host_port) in &spec.port_bindings {
+                map.insert(
+                    container_port.clone(),
+                    Some(vec![bollard::models::PortBinding {
+                        host_ip: Some("0.0.0.0".to_string()),
+                        host_port: Some(host_port.to_string()),
+                    }]),
+                );
+            }
+            Some(map)
+        };
+
+        let binds = if spec.volume_mounts.is_empty() {
+            None
+        } else {
+            Some(
+                spec.volume_mounts
+                    .iter()
+                    .map(|(host, container)| format!("{}:{}", host, container))
+                    .collect(),
+            )
+        };
+
         let config = ContainerCreateBody {
             image: Some(image_name.to_string()),
-            working_dir: working_dir.map(|s| s.to_string()),
-            env: env_vars,
+            working_dir: spec.working_dir.clone(),
+            env: spec.env_vars.clone(),
             host_config: Some(HostConfig {
                 auto_remove: Some(false),
+                memory: spec.memory_limit_bytes.map(|v| v as i64),
+                cpu_quota: spec.cpu_quota.map(|v| v as i64),
+                cpu_shares: spec.cpu_shares.map(|v| v as i64),
+                pids_limit: spec.pids_limit.map(|v| v as i64),
+                network_mode: spec.network_mode.clone(),
+                port_bindings,
+                binds,
+                readonly_rootfs: spec.read_only_rootfs,
                 ..Default::default()
             }),
+            exposed_ports: if spec.port_bindings.is_empty() {
+                None
+            } else {
+                Some(
+                    spec.port_bindings
+                        .keys()
+                        .map(|p| (p.clone(), std::collections::HashMap::new()))
+                        .collect(),
+                )
+            },
             attach_stdout: Some(true),
             attach_stderr: Some(true),
             tty: Some(true),
@@ -224,13 +613,17 @@ impl DockerManager {
         Ok(())
     }
 
-    /// Execute a command in a container with timeout
-    pub async fn exec_command(
+    /// Execute a command in a container, returning a stream of line-tagged output events.
+    ///
+    /// Unlike [`exec_command`](Self::exec_command), this does not buffer the whole output in
+    /// memory before returning: each complete stdout/stderr line is emitted as soon as it
+    /// arrives, so callers can tail long-running build/test commands live. The trailing partial
+    /// line (if the command's output doesn't end in `\n`) is flushed once the exec stream ends.
+    pub async fn exec_command_streaming(
         &self,
         container_id: &str,
         command: &[&str],
-        timeout_secs: Option<u64>,
-    ) -> DgmResult<(String, i64)> {
+    ) -> DgmResult<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
         let exec_options = CreateExecOptions {
             cmd: Some(command.iter().map(|s| s.to_string()).collect()),
             attach_stdout: Some(true),
@@ -244,63 +637,249 @@ impl DockerManager {
             .await
             .context("Failed to create exec")?;
 
-        let exec_start = self.client.start_exec(&exec.id, None).await;
+        self.exec_command_streaming_from_exec(&exec.id).await
+    }
 
-        let (output, exit_code) = match exec_start {
-            Ok(StartExecResults::Attached { mut output, .. }) => {
-                let mut stdout = Vec::new();
-                let mut stderr = Vec::new();
+    /// Start an already-created exec instance and adapt its attached output into a line stream.
+    async fn exec_command_streaming_from_exec(
+        &self,
+        exec_id: &str,
+    ) -> DgmResult<Pin<Box<dyn Stream<Item = LogLine> + Send>>> {
+        let output = match self.client.start_exec(exec_id, None).await {
+            Ok(StartExecResults::Attached { output, .. }) => output,
+            Ok(StartExecResults::Detached) => {
+                return Err(anyhow!("Unexpected detached exec result").into());
+            }
+            Err(e) => {
+                return Err(anyhow!("Failed to start exec: {}", e).into());
+            }
+        };
 
-                let collect_output = async {
-                    while let Some(Ok(msg)) = output.next().await {
-                        match msg {
-                            bollard::container::LogOutput::StdOut { message } => {
-                                stdout.extend_from_slice(&message);
-                            }
-                            bollard::container::LogOutput::StdErr { message } => {
-                                stderr.extend_from_slice(&message);
-                            }
-                            _ => {}
-                        }
+        let line_stream = stream::unfold(
+            (output, LineBuffer::default(), std::collections::VecDeque::<LogLine>::new(), false),
+            |(mut output, mut buf, mut pending, mut done)| async move {
+                loop {
+                    if let Some(line) = pending.pop_front() {
+                        return Some((line, (output, buf, pending, done)));
+                    }
+                    if done {
+                        return None;
                     }
 
-                    let mut combined_output = stdout;
-                    combined_output.extend_from_slice(&stderr);
-                    String::from_utf8_lossy(&combined_output).to_string()
-                };
-
-                let output = if let Some(timeout_duration) = timeout_secs {
-                    match timeout(Duration::from_secs(timeout_duration), collect_output).await {
-                        Ok(output) => output,
-                        Err(_) => {
-                            warn!("Command execution timed out after {} seconds", timeout_duration);
-                            return Err(anyhow!("Command execution timed out").into());
+                    match output.next().await {
+                        Some(Ok(msg)) => {
+                            let mut lines = Vec::new();
+                            match msg {
+                                bollard::container::LogOutput::StdOut { message } => {
+                                    buf.push_stdout(&message, &mut lines);
+                                }
+                                bollard::container::LogOutput::StdErr { message } => {
+                                    buf.push_stderr(&message, &mut lines);
+                                }
+                                _ => {}
+                            }
+                            // Queue every complete line; with none yet, keep polling the
+                            // underlying stream.
+                            pending.extend(lines);
+                            continue;
+                        }
+                        Some(Err(e)) => {
+                            warn!("Error reading exec output: {}", e);
+                            continue;
+                        }
+                        None => {
+                            done = true;
+                            let mut trailing = Vec::new();
+                            std::mem::take(&mut buf).flush(&mut trailing);
+                            pending.extend(trailing);
+                            continue;
                         }
                     }
-                } else {
-                    collect_output.await
-                };
-
-                // Get exit code
-                let exec_inspect = self
-                    .client
-                    .inspect_exec(&exec.id)
-                    .await
-                    .context("Failed to inspect exec")?;
-
-                let exit_code = exec_inspect.exit_code.unwrap_or(-1) as i64;
-                (output, exit_code)
+                }
+            },
+        );
+
+        Ok(Box::pin(line_stream))
+    }
+
+    /// Execute a command in a container with timeout, returning the combined output.
+    ///
+    /// Built on top of [`exec_command_streaming`](Self::exec_command_streaming): lines are
+    /// joined back into a single string for callers that don't need incremental progress.
+    pub async fn exec_command(
+        &self,
+        container_id: &str,
+        command: &[&str],
+        timeout_secs: Option<u64>,
+    ) -> DgmResult<(String, i64)> {
+        let exec_options = CreateExecOptions {
+            cmd: Some(command.iter().map(|s| s.to_string()).collect()),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            ..Default::default()
+        };
+
+        let exec = self
+            .client
+            .create_exec(container_id, exec_options)
+            .await
+            .context("Failed to create exec")?;
+
+        let mut line_stream = self.exec_command_streaming_from_exec(&exec.id).await?;
+
+        let collect_output = async {
+            let mut combined = String::new();
+            while let Some(line) = line_stream.next().await {
+                combined.push_str(line.text());
+                combined.push('\n');
             }
-            Ok(StartExecResults::Detached) => {
-                return Err(anyhow!("Unexpected detached exec result").into());
+            combined
+        };
+
+        let output = if let Some(timeout_duration) = timeout_secs {
+            match timeout(Duration::from_secs(timeout_duration), collect_output).await {
+                Ok(output) => output,
+                Err(_) => {
+                    warn!("Command execution timed out after {} seconds", timeout_duration);
+                    return Err(anyhow!("Command execution timed out").into());
+                }
             }
-            Err(e) => {
-                return Err(anyhow!("Failed to start exec: {}", e).into());
+        } else {
+            collect_output.await
+        };
+
+        // Get exit code
+        let exec_inspect = self
+            .client
+            .inspect_exec(&exec.id)
+            .await
+            .context("Failed to inspect exec")?;
+
+        let exit_code = exec_inspect.exit_code.unwrap_or(-1) as i64;
+
+        debug!("Command executed with exit code: {}", exit_code);
+        Ok((output.trim().to_string(), exit_code))
+    }
+
+    /// Like [`exec_command`](Self::exec_command), but keeps stdout and stderr in separate
+    /// buffers instead of interleaving them, for callers that need to assert on each fd
+    /// independently (e.g. per-instance `expected_output` regex checks).
+    pub async fn exec_command_split(
+        &self,
+        container_id: &str,
+        command: &[&str],
+        timeout_secs: Option<u64>,
+    ) -> DgmResult<(String, String, i64)> {
+        let exec_options = CreateExecOptions {
+            cmd: Some(command.iter().map(|s| s.to_string()).collect()),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            ..Default::default()
+        };
+
+        let exec = self
+            .client
+            .create_exec(container_id, exec_options)
+            .await
+            .context("Failed to create exec")?;
+
+        let mut line_stream = self.exec_command_streaming_from_exec(&exec.id).await?;
+
+        let collect_output = async {
+            let mut stdout = String::new();
+            let mut stderr = String::new();
+            while let Some(line) = line_stream.next().await {
+                match &line.channel {
+                    LogChannel::Stdout(text) => {
+                        stdout.push_str(text);
+                        stdout.push('\n');
+                    }
+                    LogChannel::Stderr(text) => {
+                        stderr.push_str(text);
+                        stderr.push('\n');
+                    }
+                    LogChannel::Progress(_) => {}
+                }
+            }
+            (stdout, stderr)
+        };
+
+        let (stdout, stderr) = if let Some(timeout_duration) = timeout_secs {
+            match timeout(Duration::from_secs(timeout_duration), collect_output).await {
+                Ok(output) => output,
+                Err(_) => {
+                    warn!("Command execution timed out after {} seconds", timeout_duration);
+                    return Err(anyhow!("Command execution timed out").into());
+                }
             }
+        } else {
+            collect_output.await
         };
 
+        let exec_inspect = self
+            .client
+            .inspect_exec(&exec.id)
+            .await
+            .context("Failed to inspect exec")?;
+
+        let exit_code = exec_inspect.exit_code.unwrap_or(-1) as i64;
+
         debug!("Command executed with exit code: {}", exit_code);
-        Ok((output, exit_code))
+        Ok((stdout.trim().to_string(), stderr.trim().to_string(), exit_code))
+    }
+
+    /// Like [`exec_command_streaming`](Self::exec_command_streaming), but delivers each line to
+    /// an `mpsc` sender as it is produced instead of returning a `Stream`, for callers (e.g. a
+    /// multi-hour coding-agent run) that want to tail progress via a channel - and persist it to
+    /// a log file - while it happens instead of only seeing it after the process exits. Returns
+    /// the exit code once the exec session ends, the same as [`exec_command`](Self::exec_command).
+    pub async fn exec_command_with_sender(
+        &self,
+        container_id: &str,
+        command: &[&str],
+        timeout_secs: Option<u64>,
+        sender: tokio::sync::mpsc::UnboundedSender<LogLine>,
+    ) -> DgmResult<i64> {
+        let exec_options = CreateExecOptions {
+            cmd: Some(command.iter().map(|s| s.to_string()).collect()),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            ..Default::default()
+        };
+
+        let exec = self
+            .client
+            .create_exec(container_id, exec_options)
+            .await
+            .context("Failed to create exec")?;
+
+        let mut stream = self.exec_command_streaming_from_exec(&exec.id).await?;
+
+        let forward = async {
+            while let Some(line) = stream.next().await {
+                if sender.send(line).is_err() {
+                    debug!("Receiver dropped, stopping exec output forwarding");
+                    break;
+                }
+            }
+        };
+
+        if let Some(timeout_duration) = timeout_secs {
+            if timeout(Duration::from_secs(timeout_duration), forward).await.is_err() {
+                warn!("Command execution timed out after {} seconds", timeout_duration);
+                return Err(anyhow!("Command execution timed out").into());
+            }
+        } else {
+            forward.await;
+        }
+
+        let exec_inspect = self
+            .client
+            .inspect_exec(&exec.id)
+            .await
+            .context("Failed to inspect exec")?;
+
+        Ok(exec_inspect.exit_code.unwrap_or(-1) as i64)
     }
 
     /// Copy a file or directory from local system to container
@@ -460,6 +1039,46 @@ impl DockerManager {
         Ok(())
     }
 
+    /// Check whether `image_name` is present on this daemon.
+    pub async fn image_exists(&self, image_name: &str) -> bool {
+        self.client.inspect_image(image_name).await.is_ok()
+    }
+
+    /// Make sure `image_name` exists on this daemon before it's needed by a container create,
+    /// pulling it from its registry if it's missing, instead of discovering the absence lazily
+    /// partway through an evaluation run. Serialized by `image_lock` so concurrent callers
+    /// racing on the same (or a different) image never trigger duplicate pulls against this
+    /// daemon.
+    pub async fn ensure_image(&self, image_name: &str) -> DgmResult<()> {
+        let _guard = self.image_lock.lock().await;
+
+        if self.image_exists(image_name).await {
+            return Ok(());
+        }
+
+        info!("Image '{}' not found on daemon, pulling...", image_name);
+
+        let options = bollard::query_parameters::CreateImageOptions {
+            from_image: Some(image_name.to_string()),
+            ..Default::default()
+        };
+
+        let mut stream = self.client.create_image(Some(options), None, None);
+        while let Some(result) = stream.next().await {
+            result.with_context(|| format!("Failed to pull image '{}'", image_name))?;
+        }
+
+        if !self.image_exists(image_name).await {
+            anyhow::bail!(
+                "Image '{}' is still missing after a pull attempt completed without error",
+                image_name
+            );
+        }
+
+        info!("Image '{}' pulled successfully", image_name);
+        Ok(())
+    }
+
     /// Remove a Docker image
     pub async fn remove_image(&self, image_name: &str, force: bool) -> DgmResult<()> {
         let options = RemoveImageOptions {
@@ -499,6 +1118,136 @@ impl DockerManager {
         Ok(())
     }
 
+    /// Create a user-defined bridge network, reusing it if one with the same name exists.
+    pub async fn create_network(&self, network_name: &str) -> DgmResult<String> {
+        if self
+            .client
+            .inspect_network(network_name, None::<InspectNetworkOptions>)
+            .await
+            .is_ok()
+        {
+            debug!("Network '{}' already exists", network_name);
+            return Ok(network_name.to_string());
+        }
+
+        let options = CreateNetworkOptions {
+            name: network_name.to_string(),
+            driver: "bridge".to_string(),
+            ..Default::default()
+        };
+
+        let response = self
+            .client
+            .create_network(options)
+            .await
+            .with_context(|| format!("Failed to create network '{}'", network_name))?;
+
+        info!("Network '{}' created", network_name);
+        Ok(response.id.unwrap_or_else(|| network_name.to_string()))
+    }
+
+    /// Connect a container to a network by name.
+    pub async fn connect_to_network(&self, container_id: &str, network_name: &str) -> DgmResult<()> {
+        let request = NetworkConnectRequest {
+            container: Some(container_id.to_string()),
+            ..Default::default()
+        };
+
+        self.client
+            .connect_network(network_name, request)
+            .await
+            .with_context(|| format!("Failed to connect container '{}' to network '{}'", container_id, network_name))?;
+
+        Ok(())
+    }
+
+    /// Remove a network by name.
+    pub async fn remove_network(&self, network_name: &str) -> DgmResult<()> {
+        self.client
+            .remove_network(network_name, None::<RemoveNetworkOptions>)
+            .await
+            .with_context(|| format!("Failed to remove network '{}'", network_name))?;
+
+        info!("Network '{}' removed", network_name);
+        Ok(())
+    }
+
+    /// Poll a container until it is ready, using the given [`ReadyStrategy`], backing off
+    /// between attempts and surfacing a clear error if `timeout` elapses first.
+    ///
+    /// Captures the "build, run, wait, then test" flow multi-step agent evaluations need: a
+    /// service container started via [`start_container`](Self::start_container) often isn't
+    /// actually listening yet by the time the daemon acknowledges the start.
+    pub async fn wait_until_ready(
+        &self,
+        container_id: &str,
+        strategy: ReadyStrategy,
+        timeout_duration: Duration,
+    ) -> DgmResult<()> {
+        let deadline = tokio::time::Instant::now() + timeout_duration;
+        let mut backoff = Duration::from_millis(200);
+        const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+        loop {
+            let ready = match &strategy {
+                ReadyStrategy::Healthcheck => self.is_healthy(container_id).await?,
+                ReadyStrategy::ReadinessCommand(command) => {
+                    let command: Vec<&str> = command.iter().map(|s| s.as_str()).collect();
+                    let (_, exit_code) = self.exec_command(container_id, &command, Some(10)).await?;
+                    exit_code == 0
+                }
+                ReadyStrategy::LogPattern(pattern) => self.log_matches(container_id, pattern).await?,
+            };
+
+            if ready {
+                return Ok(());
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Container '{}' did not become ready within {:?}",
+                    container_id,
+                    timeout_duration
+                )
+                .into());
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+        }
+    }
+
+    async fn is_healthy(&self, container_id: &str) -> DgmResult<bool> {
+        let inspect = self.inspect_container(container_id).await?;
+        let status = inspect
+            .state
+            .and_then(|s| s.health)
+            .and_then(|h| h.status);
+
+        Ok(matches!(status, Some(bollard::models::HealthStatusEnum::HEALTHY)))
+    }
+
+    async fn log_matches(&self, container_id: &str, pattern: &regex::Regex) -> DgmResult<bool> {
+        let logs = self
+            .client
+            .logs(
+                container_id,
+                Some(bollard::query_parameters::LogsOptionsBuilder::new().stdout(true).stderr(true).build()),
+            )
+            .map(|chunk| match chunk {
+                Ok(bollard::container::LogOutput::StdOut { message })
+                | Ok(bollard::container::LogOutput::StdErr { message }) => {
+                    String::from_utf8_lossy(&message).to_string()
+                }
+                _ => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .await
+            .join("");
+
+        Ok(pattern.is_match(&logs))
+    }
+
     /// Get container information
     pub async fn inspect_container(&self, container_id: &str) -> DgmResult<ContainerInspectResponse> {
         self.client
@@ -0,0 +1,166 @@
+use crate::utils::docker::{DockerEndpoint, DockerManager};
+use crate::DgmResult;
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tracing::debug;
+
+/// One Docker daemon the scheduler can submit work to: an independent [`DockerManager`]
+/// connection (local socket or a remote `tcp://host:port` endpoint) paired with how many
+/// containers it's allowed to run at once.
+#[derive(Debug, Clone)]
+pub struct DockerEndpointConfig {
+    pub name: String,
+    pub endpoint: DockerEndpoint,
+    pub num_max_jobs: usize,
+    /// Minimum acceptable Docker API version for this endpoint. `DockerScheduler::new` verifies
+    /// it against the daemon's negotiated version before any work starts. `None` skips the check.
+    pub min_api_version: Option<String>,
+}
+
+struct EndpointState {
+    name: String,
+    docker: Arc<DockerManager>,
+    capacity: usize,
+    in_flight: usize,
+}
+
+impl EndpointState {
+    fn load(&self) -> f64 {
+        self.in_flight as f64 / self.capacity as f64
+    }
+}
+
+/// Point-in-time utilization of one endpoint, for progress reporting.
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub name: String,
+    pub in_flight: usize,
+    pub capacity: usize,
+}
+
+/// Fans container evaluations out across multiple Docker daemons instead of pinning every
+/// SWE-bench/Polyglot container to one machine, so a large evaluation run can use a build farm.
+///
+/// Each endpoint carries its own `num_max_jobs` capacity tracked behind a plain mutex (the
+/// critical section is a quick in-memory comparison, never an await). [`submit`](Self::submit)
+/// hands the task to whichever non-saturated endpoint has the smallest in-flight/capacity ratio,
+/// and awaits a [`Notify`] when every endpoint is full. The in-flight counter is released by an
+/// RAII guard, so it's decremented on success, error, and panic alike.
+pub struct DockerScheduler {
+    endpoints: Mutex<Vec<EndpointState>>,
+    notify: Notify,
+}
+
+impl DockerScheduler {
+    /// Connect to every configured endpoint up front so a bad endpoint fails fast at
+    /// construction rather than partway through an evaluation run.
+    pub fn new(configs: Vec<DockerEndpointConfig>) -> DgmResult<Self> {
+        let mut endpoints = Vec::with_capacity(configs.len());
+        for config in configs {
+            let docker = Arc::new(DockerManager::connect(
+                config.endpoint,
+                config.min_api_version.as_deref(),
+            )?);
+            endpoints.push(EndpointState {
+                name: config.name,
+                docker,
+                capacity: config.num_max_jobs.max(1),
+                in_flight: 0,
+            });
+        }
+        Ok(Self {
+            endpoints: Mutex::new(endpoints),
+            notify: Notify::new(),
+        })
+    }
+
+    /// The connected [`DockerManager`] for every configured endpoint, in configuration order.
+    /// Used to preflight work (e.g. `ensure_image`) against every daemon the scheduler might
+    /// dispatch to, before any task is submitted.
+    pub fn docker_managers(&self) -> Vec<Arc<DockerManager>> {
+        self.endpoints.lock().unwrap().iter().map(|e| e.docker.clone()).collect()
+    }
+
+    /// Current in-flight/capacity for every endpoint, in configuration order, for progress
+    /// reporting (e.g. the admin HTTP server's `/status` and `/metrics` routes).
+    pub fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| EndpointStatus {
+                name: e.name.clone(),
+                in_flight: e.in_flight,
+                capacity: e.capacity,
+            })
+            .collect()
+    }
+
+    /// Run `task` against the least-loaded endpoint's [`DockerManager`], waiting for a free slot
+    /// if every endpoint is currently saturated.
+    pub async fn submit<F, Fut, T>(&self, task: F) -> T
+    where
+        F: FnOnce(Arc<DockerManager>) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let (index, docker) = self.acquire().await;
+        let _guard = InFlightGuard { scheduler: self, index };
+        task(docker).await
+    }
+
+    async fn acquire(&self) -> (usize, Arc<DockerManager>) {
+        loop {
+            // Register interest *before* scanning, not after: a `Notified` future counts as a
+            // registered waiter for `notify_waiters()` as soon as it's created, even unpolled.
+            // Creating it only after finding every endpoint saturated would leave a window where
+            // a concurrent `release()` could call `notify_waiters()` and have it land before we
+            // start listening — a lost wakeup that, if that release freed the last slot, would
+            // leave this task waiting forever on idle capacity.
+            let notified = self.notify.notified();
+
+            {
+                let mut endpoints = self.endpoints.lock().unwrap();
+                let picked = endpoints
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(_, e)| e.in_flight < e.capacity)
+                    .min_by(|(_, a), (_, b)| a.load().partial_cmp(&b.load()).unwrap_or(Ordering::Equal));
+
+                if let Some((index, state)) = picked {
+                    state.in_flight += 1;
+                    debug!(
+                        "Dispatching to Docker endpoint '{}' ({}/{} in flight)",
+                        state.name, state.in_flight, state.capacity
+                    );
+                    return (index, state.docker.clone());
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    fn release(&self, index: usize) {
+        {
+            let mut endpoints = self.endpoints.lock().unwrap();
+            if let Some(state) = endpoints.get_mut(index) {
+                state.in_flight = state.in_flight.saturating_sub(1);
+            }
+        }
+        self.notify.notify_waiters();
+    }
+}
+
+/// Releases the in-flight slot acquired by [`DockerScheduler::acquire`] when dropped, so a
+/// panicking or erroring task still frees its endpoint for the next one in line.
+struct InFlightGuard<'a> {
+    scheduler: &'a DockerScheduler,
+    index: usize,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.scheduler.release(self.index);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{DgmResult, Deserialize, Serialize};
+use crate::{utils::bundle, DgmResult, Deserialize, Serialize};
+use anyhow::Context;
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,7 +63,35 @@ impl PerformanceMetrics {
     }
 }
 
-/// Get model patch paths for a given commit
+/// Structured stand-in for the opaque `EvaluationResult::improvement_diagnosis` JSON blob,
+/// built from a [`crate::utils::DiffStats`] so the archive carries quantitative change-size
+/// signals (useful for biasing `choose_selfimproves` toward small, high-yield edits) instead of
+/// an untyped `serde_json::Value`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImprovementDiagnosis {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub touched_files: Vec<String>,
+    pub net_lines: i64,
+}
+
+impl ImprovementDiagnosis {
+    pub fn from_diff_stats(stats: &crate::utils::DiffStats) -> Self {
+        Self {
+            files_changed: stats.files_changed,
+            insertions: stats.insertions,
+            deletions: stats.deletions,
+            touched_files: stats.per_file.iter().map(|f| f.path.clone()).collect(),
+            net_lines: stats.insertions as i64 - stats.deletions as i64,
+        }
+    }
+}
+
+/// Get model patch paths for a given commit. Prefers a raw `model_patch.diff` in the commit's
+/// output directory; if that's absent but a [`bundle::BUNDLE_FILENAME`] bundle is present instead,
+/// the patch is extracted out of it (and verified in the process) into a sibling
+/// `model_patch.diff` so callers never need to know which form the commit was stored in.
 pub fn get_model_patch_paths(
     _root_dir: &Path,
     output_dir: &Path,
@@ -75,10 +104,23 @@ pub fn get_model_patch_paths(
         return Ok(patch_files);
     }
 
+    let commit_dir = output_dir.join(commit);
+
     // Look for model_patch.diff in the commit's output directory
-    let patch_path = output_dir.join(commit).join("model_patch.diff");
+    let patch_path = commit_dir.join("model_patch.diff");
     if patch_path.exists() {
         patch_files.push(patch_path.to_string_lossy().to_string());
+        return Ok(patch_files);
+    }
+
+    // Fall back to resolving the patch out of a content-addressed bundle, if one was archived
+    // instead of (or alongside) the raw diff.
+    let bundle_path = commit_dir.join(bundle::BUNDLE_FILENAME);
+    if bundle_path.exists() {
+        let (_, patch) = bundle::read_bundle(&bundle_path)?;
+        std::fs::write(&patch_path, patch)
+            .with_context(|| format!("Failed to extract patch from bundle at {:?}", bundle_path))?;
+        patch_files.push(patch_path.to_string_lossy().to_string());
     }
 
     Ok(patch_files)
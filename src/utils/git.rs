@@ -1,6 +1,8 @@
+use crate::utils::diff;
 use crate::DgmResult;
-use anyhow::{anyhow, Context};
+use anyhow::Context;
 use git2::{DiffOptions, Oid, Repository, ResetType, StatusOptions};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{info, warn};
@@ -11,6 +13,147 @@ pub struct GitManager {
     repo_path: PathBuf,
 }
 
+/// Outcome of [`GitManager::apply_patch`]. A patch that applied cleanly has every file in
+/// `applied_files` and both other lists empty; one that needed the three-way merge fallback may
+/// have some files land in `conflicted_files` with conflict markers instead of `.rej` files.
+#[derive(Debug, Clone, Default)]
+pub struct PatchApplyResult {
+    /// Files the patch applied to without conflicts.
+    pub applied_files: Vec<String>,
+    /// Files where the three-way merge fallback left conflict markers.
+    pub conflicted_files: Vec<String>,
+    /// Hunks that could not be reconciled, as human-readable `"<path>: <reason>"` entries.
+    pub rejected_hunks: Vec<String>,
+}
+
+impl PatchApplyResult {
+    /// True if every file applied without conflicts or rejection.
+    pub fn is_clean(&self) -> bool {
+        self.conflicted_files.is_empty() && self.rejected_hunks.is_empty()
+    }
+}
+
+/// Per-file line-count stats from [`GitManager::diff_stats_versus_commit`].
+#[derive(Debug, Clone)]
+pub struct FileDiffStat {
+    pub path: String,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Aggregate diff stats between the working tree and a commit, computed via git2's own diff
+/// stats machinery rather than by parsing the rendered patch text returned by
+/// [`GitManager::diff_versus_commit`].
+#[derive(Debug, Clone)]
+pub struct DiffStats {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub per_file: Vec<FileDiffStat>,
+}
+
+/// One changed (or added/removed) file in a [`WorkdirDiff`], with its path(s) and line hunks.
+/// `old_path` is `None` for a newly added file, `new_path` is `None` for a deleted one.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<diff::DiffHunk>,
+}
+
+impl FileDiff {
+    fn display_path(&self) -> &str {
+        self.new_path.as_deref().or(self.old_path.as_deref()).unwrap_or("<unknown>")
+    }
+
+    fn render(&self) -> String {
+        let display_path = self.display_path();
+        let old_header = self.old_path.as_deref().map(|p| format!("a/{p}")).unwrap_or_else(|| "/dev/null".to_string());
+        let new_header = self.new_path.as_deref().map(|p| format!("b/{p}")).unwrap_or_else(|| "/dev/null".to_string());
+
+        let mut out = format!("diff --git a/{display_path} b/{display_path}\n--- {old_header}\n+++ {new_header}\n");
+        for hunk in &self.hunks {
+            out.push_str(&hunk.render());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// HTML rendering of the same content `render()` produces, with each line wrapped in a
+    /// `<span>` classed by its diff role (`diff-add`/`diff-del`/`diff-ctx`) so a stylesheet can
+    /// color-code it for chat-history logging, instead of a reviewer skimming a plain-text patch.
+    fn render_html(&self) -> String {
+        let mut out = format!(
+            "<div class=\"diff-file\"><h4>{}</h4><pre>\n",
+            html_escape(self.display_path())
+        );
+        for hunk in &self.hunks {
+            out.push_str(&format!(
+                "<span class=\"diff-hunk-header\">{}</span>\n",
+                html_escape(&format!(
+                    "@@ -{},{} +{},{} @@",
+                    hunk.old_start, hunk.old_len, hunk.new_start, hunk.new_len
+                ))
+            ));
+            for line in &hunk.lines {
+                let class = match line.as_bytes().first() {
+                    Some(b'+') => "diff-add",
+                    Some(b'-') => "diff-del",
+                    _ => "diff-ctx",
+                };
+                out.push_str(&format!("<span class=\"{}\">{}</span>\n", class, html_escape(line)));
+            }
+        }
+        out.push_str("</pre></div>\n");
+        out
+    }
+}
+
+/// Minimal HTML entity escaping for [`FileDiff::render_html`]; diff lines are plain source text,
+/// never markup, so only the five XML-unsafe characters need escaping.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Structured result of [`GitManager::diff_versus_commit`]: one [`FileDiff`] per changed path,
+/// so the evolution loop can inspect what changed file-by-file instead of re-parsing a flat
+/// patch string.
+#[derive(Debug, Clone, Default)]
+pub struct WorkdirDiff {
+    pub files: Vec<FileDiff>,
+}
+
+impl WorkdirDiff {
+    /// Render as a single unified-diff string, e.g. for writing `model_patch.diff`.
+    pub fn render(&self) -> String {
+        self.files.iter().map(FileDiff::render).collect::<Vec<_>>().join("")
+    }
+
+    /// Syntax-highlighted (diff-role color-coded) HTML rendering of the same diff, for opt-in
+    /// use in chat-history logging where a reviewer skims rather than diffing two plain-text
+    /// files side by side. See [`FileDiff::render_html`].
+    pub fn render_html(&self) -> String {
+        let mut out = String::from("<div class=\"workdir-diff\">\n");
+        for file in &self.files {
+            out.push_str(&file.render_html());
+        }
+        out.push_str("</div>\n");
+        out
+    }
+}
+
+/// A local branch, as reported by [`GitManager::branches`].
+#[derive(Debug, Clone)]
+pub struct Branch {
+    pub name: String,
+    /// Unix timestamp (seconds) of the branch tip's commit.
+    pub last_commit_time: i64,
+}
+
 impl GitManager {
     /// Create a new Git manager for the repository at the given path
     pub fn new(path: &Path) -> DgmResult<Self> {
@@ -29,8 +172,90 @@ impl GitManager {
         Ok(commit.id().to_string())
     }
 
-    /// Get diff between current state and a specific commit
-    pub fn diff_versus_commit(&self, commit_hash: &str) -> DgmResult<String> {
+    /// Diff the working tree against `commit_hash` entirely in-process via `gix`, instead of
+    /// libgit2's own diff engine: snapshot every blob in the commit's tree, snapshot every file
+    /// on disk (an ignore-aware walk like [`crate::tools::fs::RealFs::read_dir`]'s, but with no
+    /// depth limit, hidden files included and `.git` skipped), and feed
+    /// any path whose content differs between the two through [`diff::diff_hunks`]. This avoids
+    /// depending on libgit2's diff machinery being built/configured consistently across hosts,
+    /// and returns per-file hunks the evolution loop can inspect rather than an opaque blob -
+    /// callers that just want `git diff`-style text can still call [`WorkdirDiff::render`].
+    pub fn diff_versus_commit(&self, commit_hash: &str) -> DgmResult<WorkdirDiff> {
+        let gix_repo = gix::open(&self.repo_path)
+            .with_context(|| format!("Failed to open {:?} via gix", self.repo_path))?;
+
+        let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes())
+            .with_context(|| format!("Invalid commit hash: {}", commit_hash))?;
+        let commit = gix_repo.find_object(commit_id)
+            .with_context(|| format!("Failed to find commit: {}", commit_hash))?
+            .try_into_commit()
+            .with_context(|| format!("{} is not a commit", commit_hash))?;
+        let tree = commit.tree().context("Failed to get commit tree")?;
+
+        let mut old_blobs: HashMap<String, Vec<u8>> = HashMap::new();
+        for entry in tree.traverse().breadthfirst.files().context("Failed to walk commit tree")? {
+            let path = entry.filepath.to_string();
+            let blob = gix_repo.find_object(entry.oid)
+                .with_context(|| format!("Failed to read blob for {}", path))?;
+            old_blobs.insert(path, blob.data.clone());
+        }
+
+        let mut new_blobs: HashMap<String, Vec<u8>> = HashMap::new();
+        let walker = ignore::WalkBuilder::new(&self.repo_path)
+            .hidden(false)
+            .git_ignore(true)
+            .git_global(true)
+            .git_exclude(true)
+            .build();
+        for entry in walker {
+            let entry = entry.context("Failed to walk working directory")?;
+            if entry.path().components().any(|c| c.as_os_str() == ".git") {
+                continue;
+            }
+            if entry.file_type().map(|t| !t.is_file()).unwrap_or(true) {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&self.repo_path).unwrap_or(entry.path());
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let content = std::fs::read(entry.path())
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            new_blobs.insert(relative, content);
+        }
+
+        let mut paths: Vec<&String> = old_blobs.keys().chain(new_blobs.keys()).collect();
+        paths.sort();
+        paths.dedup();
+
+        let mut files = Vec::new();
+        for path in paths {
+            let old = old_blobs.get(path);
+            let new = new_blobs.get(path);
+            if old.map(Vec::as_slice) == new.map(Vec::as_slice) {
+                continue;
+            }
+
+            let old_text = old.map(|b| String::from_utf8_lossy(b).into_owned()).unwrap_or_default();
+            let new_text = new.map(|b| String::from_utf8_lossy(b).into_owned()).unwrap_or_default();
+            let hunks = diff::diff_hunks(&old_text, &new_text, 3);
+            if hunks.is_empty() {
+                continue;
+            }
+
+            files.push(FileDiff {
+                old_path: old.map(|_| path.clone()),
+                new_path: new.map(|_| path.clone()),
+                hunks,
+            });
+        }
+
+        Ok(WorkdirDiff { files })
+    }
+
+    /// Structured insertions/deletions/files-changed stats between the working tree and
+    /// `commit_hash`, plus a per-file breakdown, so callers that need quantitative change-size
+    /// signals (e.g. [`ImprovementDiagnosis`](crate::utils::ImprovementDiagnosis)) don't have to
+    /// count lines out of the [`WorkdirDiff`] [`Self::diff_versus_commit`] returns.
+    pub fn diff_stats_versus_commit(&self, commit_hash: &str) -> DgmResult<DiffStats> {
         let commit_oid = Oid::from_str(commit_hash)
             .with_context(|| format!("Invalid commit hash: {}", commit_hash))?;
 
@@ -39,7 +264,6 @@ impl GitManager {
 
         let commit_tree = commit.tree().context("Failed to get commit tree")?;
 
-        // Get diff between commit and working directory
         let mut diff_opts = DiffOptions::new();
         diff_opts.include_untracked(true);
         diff_opts.recurse_untracked_dirs(true);
@@ -47,52 +271,152 @@ impl GitManager {
         let diff = self.repo.diff_tree_to_workdir_with_index(Some(&commit_tree), Some(&mut diff_opts))
             .context("Failed to create diff")?;
 
-        // Convert diff to string
-        let mut diff_output = String::new();
-        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-            match line.origin() {
-                '+' | '-' | ' ' => diff_output.push(line.origin()),
-                _ => {}
-            }
-            diff_output.push_str(std::str::from_utf8(line.content()).unwrap_or(""));
-            true
-        }).context("Failed to format diff")?;
+        let stats = diff.stats().context("Failed to compute diff stats")?;
+
+        let mut per_file = Vec::new();
+        for i in 0..diff.deltas().len() {
+            let path = diff.get_delta(i)
+                .and_then(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| "<unknown>".to_string());
 
-        Ok(diff_output)
+            let (insertions, deletions) = match git2::Patch::from_diff(&diff, i) {
+                Ok(Some(mut patch)) => patch
+                    .line_stats()
+                    .map(|(_, insertions, deletions)| (insertions, deletions))
+                    .unwrap_or((0, 0)),
+                _ => (0, 0),
+            };
+
+            per_file.push(FileDiffStat { path, insertions, deletions });
+        }
+
+        Ok(DiffStats {
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+            per_file,
+        })
     }
 
-    /// Apply a patch to the repository
-    pub fn apply_patch(&self, patch_str: &str) -> DgmResult<()> {
+    /// Apply a patch to the repository natively via git2, falling back to a three-way merge
+    /// (against HEAD as the ancestor) when it doesn't apply cleanly, instead of shelling out to
+    /// `git apply --reject` and leaving `.rej` files behind. Returns which files applied,
+    /// which ended up with conflict markers, and which hunks could not be reconciled at all, so
+    /// callers can record a partial-application outcome instead of a bare success/error.
+    pub fn apply_patch(&self, patch_str: &str) -> DgmResult<PatchApplyResult> {
         info!("Applying patch to repository");
 
-        // Use git command line for patch application as it's more reliable
-        let output = Command::new("git")
-            .args(&["-C", &self.repo_path.to_string_lossy(), "apply", "--reject", "-"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .spawn()
-            .context("Failed to spawn git apply command")?;
-
-        let mut child = output;
-        if let Some(stdin) = child.stdin.as_mut() {
-            use std::io::Write;
-            stdin.write_all(patch_str.as_bytes())
-                .context("Failed to write patch to git apply stdin")?;
+        let diff = git2::Diff::from_buffer(patch_str.as_bytes())
+            .context("Failed to parse patch as a git diff")?;
+
+        let patch_files: Vec<String> = (0..diff.deltas().len())
+            .filter_map(|i| diff.get_delta(i))
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+
+        match self.repo.apply(&diff, git2::ApplyLocation::WorkDir, None) {
+            Ok(()) => {
+                info!("Patch applied cleanly to {} file(s)", patch_files.len());
+                Ok(PatchApplyResult {
+                    applied_files: patch_files,
+                    conflicted_files: Vec::new(),
+                    rejected_hunks: Vec::new(),
+                })
+            }
+            Err(e) => {
+                warn!("Clean apply failed ({}), falling back to three-way merge", e);
+                self.apply_patch_three_way(&diff, &patch_files)
+            }
         }
+    }
 
-        let output = child.wait_with_output()
-            .context("Failed to wait for git apply command")?;
+    /// Three-way merge fallback for [`Self::apply_patch`]. Builds the tree the patch would
+    /// produce on top of HEAD (`apply_to_tree`), then merges that against the current working
+    /// tree with HEAD as the common ancestor (`merge_trees`), so hunks that don't cleanly apply
+    /// come out as conflict markers in the checked-out files rather than `.rej` files.
+    fn apply_patch_three_way(
+        &self,
+        diff: &git2::Diff,
+        patch_files: &[String],
+    ) -> DgmResult<PatchApplyResult> {
+        let head_commit = self.repo.head().context("Failed to get HEAD reference")?
+            .peel_to_commit().context("Failed to peel HEAD to commit")?;
+        let ancestor_tree = head_commit.tree().context("Failed to get HEAD tree")?;
+
+        let patched_index = self.repo.apply_to_tree(&ancestor_tree, diff, None)
+            .context("Failed to build patched tree from diff")?;
+        let patched_tree_oid = patched_index.write_tree_to(&self.repo)
+            .context("Failed to write patched tree")?;
+        let patched_tree = self.repo.find_tree(patched_tree_oid)
+            .context("Failed to load patched tree")?;
+
+        // Stage the current working tree so it can stand in as the "our" side of the merge.
+        let mut index = self.repo.index().context("Failed to get repository index")?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .context("Failed to stage working tree changes")?;
+        let workdir_tree_id = index.write_tree().context("Failed to write working tree")?;
+        let workdir_tree = self.repo.find_tree(workdir_tree_id).context("Failed to load working tree")?;
+
+        let mut merged_index = self.repo
+            .merge_trees(&ancestor_tree, &workdir_tree, &patched_tree, None)
+            .context("Failed to three-way merge patched tree into working tree")?;
+
+        let mut conflicted_files = Vec::new();
+        let mut rejected_hunks = Vec::new();
+        if merged_index.has_conflicts() {
+            for conflict in merged_index.conflicts().context("Failed to read merge conflicts")? {
+                let conflict = conflict.context("Failed to read a merge conflict entry")?;
+                let path = conflict.our.as_ref()
+                    .or(conflict.their.as_ref())
+                    .or(conflict.ancestor.as_ref())
+                    .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+                    .unwrap_or_else(|| "<unknown>".to_string());
+                rejected_hunks.push(format!("{}: hunk could not be applied cleanly", path));
+                conflicted_files.push(path);
+            }
+        }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            warn!("Patch did not fully apply. stdout: {}, stderr: {}", stdout, stderr);
-            return Err(anyhow!("Failed to apply patch: {}", stderr).into());
+        // Check the merge result (including any conflict markers) out to the working directory.
+        let mut checkout_opts = git2::build::CheckoutBuilder::new();
+        checkout_opts.force();
+        self.repo.checkout_index(Some(&mut merged_index), Some(&mut checkout_opts))
+            .context("Failed to checkout three-way merge result")?;
+
+        let applied_files: Vec<String> = patch_files.iter()
+            .filter(|f| !conflicted_files.contains(f))
+            .cloned()
+            .collect();
+
+        if conflicted_files.is_empty() {
+            info!("Three-way merge applied cleanly to {} file(s)", applied_files.len());
+        } else {
+            warn!(
+                "Three-way merge left {} file(s) with conflict markers: {:?}",
+                conflicted_files.len(),
+                conflicted_files
+            );
         }
 
-        info!("Patch applied successfully");
-        Ok(())
+        Ok(PatchApplyResult { applied_files, conflicted_files, rejected_hunks })
+    }
+
+    /// Resolve `commit_hash` to its tree id, parent commit id (if any), author name, and commit
+    /// timestamp, for [`crate::utils::RepoPool::cached_diff`] to memoize without re-walking the
+    /// commit object on every access.
+    pub fn commit_metadata(&self, commit_hash: &str) -> DgmResult<(String, Option<String>, String, i64)> {
+        let commit_oid = Oid::from_str(commit_hash)
+            .with_context(|| format!("Invalid commit hash: {}", commit_hash))?;
+        let commit = self.repo.find_commit(commit_oid)
+            .with_context(|| format!("Failed to find commit: {}", commit_hash))?;
+
+        let tree_id = commit.tree_id().to_string();
+        let parent = commit.parent_id(0).ok().map(|oid| oid.to_string());
+        let author = commit.author().name().unwrap_or("<unknown>").to_string();
+        let timestamp = commit.time().seconds();
+
+        Ok((tree_id, parent, author, timestamp))
     }
 
     /// Reset repository to a specific commit
@@ -124,46 +448,96 @@ impl GitManager {
         Ok(())
     }
 
-    /// Filter patch to only include changes for specific files
-    pub fn filter_patch_by_files(&self, patch_str: &str, target_files: &[&str]) -> String {
-        let lines: Vec<&str> = patch_str.lines().collect();
-        let mut filtered_lines = Vec::new();
-        let mut include_block = false;
-
-        for line in lines {
-            // Check if this is a new diff block header
-            if line.starts_with("diff --git") {
-                include_block = target_files.iter().any(|target| {
-                    line.contains(&format!("a/{}", target)) && line.contains(&format!("b/{}", target))
-                });
-            }
-
-            if include_block {
-                filtered_lines.push(line);
-            }
+    /// List local branches, each with its tip commit's timestamp, so callers can inspect branch
+    /// activity (e.g. find a self-improvement candidate's branch) without shelling out to `git
+    /// branch`.
+    pub fn branches(&self) -> DgmResult<Vec<Branch>> {
+        let mut branches = Vec::new();
+
+        for branch in self.repo.branches(Some(git2::BranchType::Local))
+            .context("Failed to list branches")? {
+            let (branch, _branch_type) = branch.context("Failed to read branch entry")?;
+            let name = branch.name().context("Failed to read branch name")?
+                .unwrap_or("<invalid utf-8>")
+                .to_string();
+            let commit = branch.get().peel_to_commit()
+                .with_context(|| format!("Failed to peel branch {} to commit", name))?;
+
+            branches.push(Branch { name, last_commit_time: commit.time().seconds() });
         }
 
-        filtered_lines.join("\n")
+        Ok(branches)
     }
 
-    /// Remove patch blocks for files containing a keyword
-    pub fn remove_patch_by_files(&self, patch_str: &str, keyword: &str) -> String {
-        let lines: Vec<&str> = patch_str.lines().collect();
-        let mut filtered_lines = Vec::new();
-        let mut include_block = true;
-
-        for line in lines {
-            // Check if this is a new diff block header
-            if line.starts_with("diff --git") {
-                include_block = !line.to_lowercase().contains(&keyword.to_lowercase());
-            }
+    /// Create a local branch named `name` pointing at `from_commit`, so a self-improvement
+    /// candidate can get its own line of history off its parent commit instead of every
+    /// candidate serializing through the same checked-out working tree.
+    pub fn create_branch(&self, name: &str, from_commit: &str) -> DgmResult<()> {
+        let commit_oid = Oid::from_str(from_commit)
+            .with_context(|| format!("Invalid commit hash: {}", from_commit))?;
+        let commit = self.repo.find_commit(commit_oid)
+            .with_context(|| format!("Failed to find commit: {}", from_commit))?;
+
+        self.repo.branch(name, &commit, false)
+            .with_context(|| format!("Failed to create branch {} at {}", name, from_commit))?;
+
+        info!("Created branch {} at {}", name, from_commit);
+        Ok(())
+    }
+
+    /// Check out `name`, updating HEAD and the working directory/index to match its tip.
+    pub fn checkout_branch(&self, name: &str) -> DgmResult<()> {
+        let branch_ref = format!("refs/heads/{}", name);
+        let obj = self.repo.revparse_single(&branch_ref)
+            .with_context(|| format!("Failed to resolve branch {}", name))?;
+
+        let mut checkout_opts = git2::build::CheckoutBuilder::new();
+        checkout_opts.force();
+        self.repo.checkout_tree(&obj, Some(&mut checkout_opts))
+            .with_context(|| format!("Failed to checkout tree for branch {}", name))?;
+
+        self.repo.set_head(&branch_ref)
+            .with_context(|| format!("Failed to set HEAD to branch {}", name))?;
 
-            if include_block {
-                filtered_lines.push(line);
+        info!("Checked out branch {}", name);
+        Ok(())
+    }
+
+    /// Delete the local branch `name`, once its candidate's evaluation has been recorded.
+    pub fn delete_branch(&self, name: &str) -> DgmResult<()> {
+        let mut branch = self.repo.find_branch(name, git2::BranchType::Local)
+            .with_context(|| format!("Failed to find branch {}", name))?;
+        branch.delete().with_context(|| format!("Failed to delete branch {}", name))?;
+
+        info!("Deleted branch {}", name);
+        Ok(())
+    }
+
+    /// Filter patch to only include changes for specific files. Parses the patch into
+    /// [`crate::utils::ParsedPatch`] deltas first, so a target like `"a.rs"` matches exactly
+    /// rather than as a substring of `"a.rs.bak"`, and renamed/copied files still match on
+    /// whichever side (old or new path) was given.
+    pub fn filter_patch_by_files(&self, patch_str: &str, target_files: &[&str]) -> String {
+        match crate::utils::ParsedPatch::parse(patch_str) {
+            Ok(parsed) => parsed.filter_by_files(target_files).serialize(),
+            Err(e) => {
+                warn!("Failed to parse patch for filtering, returning empty patch: {}", e);
+                String::new()
             }
         }
+    }
 
-        filtered_lines.join("\n")
+    /// Remove patch blocks for files whose path contains a keyword. Matches against the
+    /// delta's actual old/new paths rather than scanning the raw `diff --git` line, so it
+    /// survives renames and paths containing spaces.
+    pub fn remove_patch_by_files(&self, patch_str: &str, keyword: &str) -> String {
+        match crate::utils::ParsedPatch::parse(patch_str) {
+            Ok(parsed) => parsed.remove_by_keyword(keyword).serialize(),
+            Err(e) => {
+                warn!("Failed to parse patch for filtering, returning original patch: {}", e);
+                patch_str.to_string()
+            }
+        }
     }
 
     /// Get repository status (modified, untracked files)
@@ -1,9 +1,33 @@
+pub mod backup;
+pub mod batch;
+pub mod bundle;
+pub mod code_action;
 pub mod common;
+pub mod compose;
 pub mod docker;
+pub mod docker_scheduler;
+pub mod diff;
 pub mod eval;
 pub mod git;
+pub mod patch;
+pub mod repo_pool;
+pub mod scheduler;
+pub mod suggestion;
+pub mod test_selector;
 
+pub use backup::*;
+pub use batch::*;
+pub use bundle::*;
+pub use code_action::*;
 pub use common::*;
+pub use compose::*;
+pub use diff::*;
 pub use docker::*;
+pub use docker_scheduler::*;
 pub use eval::*;
 pub use git::*;
+pub use patch::*;
+pub use repo_pool::*;
+pub use scheduler::*;
+pub use suggestion::*;
+pub use test_selector::*;
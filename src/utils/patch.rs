@@ -0,0 +1,211 @@
+use crate::DgmResult;
+
+/// What kind of change a [`FileDelta`] represents, as determined from its `diff --git` header
+/// and the extended-header lines that follow it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    Binary,
+}
+
+/// One file's worth of a unified diff: its old/new paths, what kind of change it is, and the raw
+/// header-plus-hunk text needed to reproduce it via [`ParsedPatch::serialize`].
+#[derive(Debug, Clone)]
+pub struct FileDelta {
+    pub old_path: String,
+    pub new_path: String,
+    pub kind: ChangeKind,
+    raw: String,
+}
+
+impl FileDelta {
+    /// True if `path` names either side of this delta, by exact match rather than substring.
+    pub fn touches(&self, path: &str) -> bool {
+        self.old_path == path || self.new_path == path
+    }
+}
+
+/// A unified diff, tokenized into one [`FileDelta`] per `diff --git` block instead of treated as
+/// opaque text, so filtering by file can match paths exactly and survives renames, copies,
+/// mode-only changes, and binary deltas.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedPatch {
+    pub deltas: Vec<FileDelta>,
+}
+
+impl ParsedPatch {
+    /// Parse a unified diff produced by `git diff` / `git format-patch`.
+    pub fn parse(patch_str: &str) -> DgmResult<Self> {
+        let mut deltas = Vec::new();
+        let mut current: Option<DeltaBuilder> = None;
+
+        for line in patch_str.lines() {
+            if line.starts_with("diff --git ") {
+                if let Some(builder) = current.take() {
+                    deltas.push(builder.finish());
+                }
+                current = Some(DeltaBuilder::new(line));
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(builder) => builder.push_line(line),
+                // Content before the first `diff --git` header (e.g. a commit message preamble
+                // in a `git format-patch` file) isn't attributable to any delta; drop it.
+                None => continue,
+            }
+        }
+
+        if let Some(builder) = current.take() {
+            deltas.push(builder.finish());
+        }
+
+        Ok(Self { deltas })
+    }
+
+    /// Keep only deltas that touch one of `target_files` (exact path match on either side).
+    pub fn filter_by_files(&self, target_files: &[&str]) -> ParsedPatch {
+        let deltas = self
+            .deltas
+            .iter()
+            .filter(|delta| target_files.iter().any(|target| delta.touches(target)))
+            .cloned()
+            .collect();
+        ParsedPatch { deltas }
+    }
+
+    /// Drop deltas whose old or new path contains `keyword` (case-insensitive).
+    pub fn remove_by_keyword(&self, keyword: &str) -> ParsedPatch {
+        let keyword = keyword.to_lowercase();
+        let deltas = self
+            .deltas
+            .iter()
+            .filter(|delta| {
+                !delta.old_path.to_lowercase().contains(&keyword)
+                    && !delta.new_path.to_lowercase().contains(&keyword)
+            })
+            .cloned()
+            .collect();
+        ParsedPatch { deltas }
+    }
+
+    /// Re-emit only the selected deltas as a unified diff, so filtering round-trips back to
+    /// something `git apply` (or [`GitManager::apply_patch`](crate::utils::GitManager::apply_patch))
+    /// can consume.
+    pub fn serialize(&self) -> String {
+        self.deltas
+            .iter()
+            .map(|delta| delta.raw.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Accumulates the lines of one `diff --git` block while its metadata is still being
+/// discovered, so [`ParsedPatch::parse`] can fill in paths and kind as later header lines
+/// (`rename from`, `new file mode`, `Binary files ... differ`, ...) are seen.
+struct DeltaBuilder {
+    old_path: String,
+    new_path: String,
+    kind: ChangeKind,
+    raw: Vec<String>,
+    renamed_or_copied: bool,
+}
+
+impl DeltaBuilder {
+    fn new(header_line: &str) -> Self {
+        let (old_path, new_path) = parse_diff_git_header(header_line);
+        Self {
+            old_path,
+            new_path,
+            kind: ChangeKind::Modified,
+            raw: vec![header_line.to_string()],
+            renamed_or_copied: false,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        if let Some(path) = line.strip_prefix("rename from ") {
+            self.old_path = path.to_string();
+            self.kind = ChangeKind::Renamed;
+            self.renamed_or_copied = true;
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            self.new_path = path.to_string();
+            self.kind = ChangeKind::Renamed;
+            self.renamed_or_copied = true;
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            self.old_path = path.to_string();
+            self.kind = ChangeKind::Copied;
+            self.renamed_or_copied = true;
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            self.new_path = path.to_string();
+            self.kind = ChangeKind::Copied;
+            self.renamed_or_copied = true;
+        } else if line.starts_with("new file mode") {
+            if !self.renamed_or_copied {
+                self.kind = ChangeKind::Added;
+            }
+        } else if line.starts_with("deleted file mode") {
+            if !self.renamed_or_copied {
+                self.kind = ChangeKind::Deleted;
+            }
+        } else if let Some(rest) = line.strip_prefix("Binary files ") {
+            self.kind = ChangeKind::Binary;
+            if let Some(paths) = rest.strip_suffix(" differ") {
+                if let Some((old, new)) = paths.split_once(" and ") {
+                    self.old_path = strip_ab_prefix(old);
+                    self.new_path = strip_ab_prefix(new);
+                }
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if path != "/dev/null" {
+                self.old_path = strip_ab_prefix(path);
+            }
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if path != "/dev/null" {
+                self.new_path = strip_ab_prefix(path);
+            }
+        }
+
+        self.raw.push(line.to_string());
+    }
+
+    fn finish(self) -> FileDelta {
+        FileDelta {
+            old_path: self.old_path,
+            new_path: self.new_path,
+            kind: self.kind,
+            raw: self.raw.join("\n"),
+        }
+    }
+}
+
+/// Strip a leading `a/` or `b/` prefix (as used by `---`/`+++`/`Binary files` lines), leaving the
+/// path untouched if there isn't one.
+fn strip_ab_prefix(path: &str) -> String {
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// Best-effort split of a `diff --git a/<old> b/<new>` header into its two paths. Authoritative
+/// for the common case; callers should prefer the `---`/`+++`/`rename from`/`rename to` lines
+/// that follow, which are unambiguous even when the path itself contains `" b/"`.
+fn parse_diff_git_header(line: &str) -> (String, String) {
+    let rest = line.strip_prefix("diff --git ").unwrap_or(line);
+
+    match rest.find(" b/") {
+        Some(idx) => {
+            let old = rest[..idx].strip_prefix("a/").unwrap_or(&rest[..idx]);
+            let new = &rest[idx + 1..];
+            let new = new.strip_prefix("b/").unwrap_or(new);
+            (old.to_string(), new.to_string())
+        }
+        None => (rest.to_string(), rest.to_string()),
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{utils::git::GitManager, DgmResult};
+use moka::sync::Cache;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Resolved metadata and diff for one commit, as cached by [`RepoPool::cached_diff`] so repeated
+/// archive traversal doesn't re-read the same commit object or re-render the same diff.
+#[derive(Debug, Clone)]
+pub struct CachedCommitInfo {
+    pub tree_id: String,
+    pub parent: Option<String>,
+    pub author: String,
+    pub timestamp: i64,
+    pub diff: String,
+}
+
+/// Cache key for [`RepoPool::cached_diff`]: a commit id, scoped to the repo it was resolved
+/// against, since commit ids aren't unique across repos.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CommitCacheKey {
+    repo_path: PathBuf,
+    commit: String,
+}
+
+/// Memoizes opened [`GitManager`] handles (keyed by repo path, evicted after a period of
+/// inactivity) and resolved commit metadata/diffs (keyed by repo + commit, evicted after a short
+/// time-to-live), so the evolution loop doesn't call `Repository::open`/`find_commit` and
+/// re-render the same diff on every generation as the archive grows.
+pub struct RepoPool {
+    repos: Cache<PathBuf, Arc<GitManager>>,
+    diffs: Cache<CommitCacheKey, CachedCommitInfo>,
+}
+
+impl RepoPool {
+    /// `repo_idle` is how long an unused [`GitManager`] handle stays cached; `diff_ttl` is how
+    /// long a resolved commit's metadata/diff stays valid before it's recomputed.
+    pub fn new(repo_idle: Duration, diff_ttl: Duration) -> Self {
+        Self {
+            repos: Cache::builder().time_to_idle(repo_idle).build(),
+            diffs: Cache::builder().time_to_live(diff_ttl).build(),
+        }
+    }
+
+    /// Get (opening and caching, if not already warm) the [`GitManager`] for the repository at
+    /// `path`.
+    pub fn get_repo(&self, path: &Path) -> DgmResult<Arc<GitManager>> {
+        if let Some(repo) = self.repos.get(&path.to_path_buf()) {
+            return Ok(repo);
+        }
+
+        let repo = Arc::new(GitManager::new(path)?);
+        self.repos.insert(path.to_path_buf(), repo.clone());
+        debug!("Opened and cached repository at {:?}", path);
+        Ok(repo)
+    }
+
+    /// Resolve `commit`'s metadata and diff-versus-working-tree text against the repo at
+    /// `repo_path`, memoizing the result for this pool's `diff_ttl`.
+    pub fn cached_diff(&self, repo_path: &Path, commit: &str) -> DgmResult<CachedCommitInfo> {
+        let key = CommitCacheKey {
+            repo_path: repo_path.to_path_buf(),
+            commit: commit.to_string(),
+        };
+
+        if let Some(cached) = self.diffs.get(&key) {
+            return Ok(cached);
+        }
+
+        let repo = self.get_repo(repo_path)?;
+        let diff = repo.diff_versus_commit(commit)?.render();
+        let (tree_id, parent, author, timestamp) = repo.commit_metadata(commit)?;
+
+        let info = CachedCommitInfo { tree_id, parent, author, timestamp, diff };
+        self.diffs.insert(key, info.clone());
+        Ok(info)
+    }
+
+    /// Drop a cached repo handle and its cached diffs, e.g. after
+    /// [`GitManager::reset_to_commit`] changes what's checked out so stale diffs shouldn't keep
+    /// being served from `cached_diff`.
+    pub fn invalidate_repo(&self, path: &Path) {
+        self.repos.invalidate(&path.to_path_buf());
+        let path = path.to_path_buf();
+        self.diffs.invalidate_entries_if(move |key, _| key.repo_path == path)
+            .ok();
+    }
+}
+
+impl Default for RepoPool {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(300), Duration::from_secs(30))
+    }
+}
+
+impl std::fmt::Debug for RepoPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RepoPool")
+            .field("repos", &self.repos.entry_count())
+            .field("diffs", &self.diffs.entry_count())
+            .finish()
+    }
+}
@@ -0,0 +1,142 @@
+use crate::utils::docker::DockerManager;
+use crate::DgmResult;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+
+/// A unit of work to run in its own container.
+#[derive(Debug, Clone)]
+pub struct ContainerJob {
+    pub job_id: String,
+    pub image: String,
+    pub command: Vec<String>,
+    pub env_vars: Option<Vec<String>>,
+    pub timeout_secs: Option<u64>,
+}
+
+/// Outcome of running a single [`ContainerJob`].
+#[derive(Debug, Clone)]
+pub struct JobResult {
+    pub job_id: String,
+    pub output: String,
+    pub exit_code: i64,
+}
+
+/// Runs a queue of [`ContainerJob`]s against [`DockerManager`], capping the number of containers
+/// in flight at once so a large evolutionary batch doesn't thrash the host.
+///
+/// Modeled on butido's scheduler: a `tokio::sync::Semaphore` gates `num_max_jobs` concurrent
+/// containers, and each job's container is stopped and removed once the job finishes, fails or
+/// times out. A panic inside a job's task skips that cleanup.
+pub struct JobScheduler {
+    docker: Arc<DockerManager>,
+    semaphore: Arc<Semaphore>,
+}
+
+impl JobScheduler {
+    pub fn new(docker: Arc<DockerManager>, num_max_jobs: usize) -> Self {
+        Self {
+            docker,
+            semaphore: Arc::new(Semaphore::new(num_max_jobs)),
+        }
+    }
+
+    /// Run every job in `jobs`, respecting the configured concurrency limit, and return all
+    /// results once every job has finished (successfully, with an error, or via timeout).
+    pub async fn run_all(&self, jobs: Vec<ContainerJob>) -> Vec<JobResult> {
+        let mut handles = Vec::with_capacity(jobs.len());
+
+        for job in jobs {
+            let semaphore = self.semaphore.clone();
+            let docker = self.docker.clone();
+
+            handles.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.expect("semaphore closed");
+                Self::run_one(docker, job).await
+            }));
+        }
+
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    warn!("Job task panicked: {}", e);
+                    results.push(JobResult {
+                        job_id: "unknown".to_string(),
+                        output: String::new(),
+                        exit_code: -1,
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Create, run, and clean up the container backing a single job, even on timeout.
+    async fn run_one(docker: Arc<DockerManager>, job: ContainerJob) -> JobResult {
+        let container_name = format!("dgm_job_{}", job.job_id);
+
+        let container_id = match docker
+            .create_container(&job.image, &container_name, None, job.env_vars.clone())
+            .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                warn!("Job '{}' failed to create container: {}", job.job_id, e);
+                return JobResult {
+                    job_id: job.job_id,
+                    output: String::new(),
+                    exit_code: -1,
+                };
+            }
+        };
+
+        // Tear the container down after the job body returns, whether it succeeded, failed or
+        // timed out.
+        let run_result = Self::exec_with_cleanup(&docker, &container_id, &job).await;
+
+        if let Err(e) = docker.stop_container(&container_id, 5).await {
+            warn!("Failed to stop job '{}' container: {}", job.job_id, e);
+        }
+        if let Err(e) = docker.remove_container(&container_id, true).await {
+            warn!("Failed to remove job '{}' container: {}", job.job_id, e);
+        }
+
+        match run_result {
+            Ok((output, exit_code)) => {
+                info!("Job '{}' completed with exit code {}", job.job_id, exit_code);
+                JobResult {
+                    job_id: job.job_id,
+                    output,
+                    exit_code,
+                }
+            }
+            Err(e) => {
+                warn!("Job '{}' failed: {}", job.job_id, e);
+                JobResult {
+                    job_id: job.job_id,
+                    output: String::new(),
+                    exit_code: -1,
+                }
+            }
+        }
+    }
+
+    async fn exec_with_cleanup(
+        docker: &DockerManager,
+        container_id: &str,
+        job: &ContainerJob,
+    ) -> DgmResult<(String, i64)> {
+        docker.start_container(container_id).await?;
+
+        let command: Vec<&str> = job.command.iter().map(|s| s.as_str()).collect();
+        let timeout_secs = job.timeout_secs.unwrap_or(Duration::from_secs(3600).as_secs());
+
+        docker
+            .exec_command(container_id, &command, Some(timeout_secs))
+            .await
+    }
+}
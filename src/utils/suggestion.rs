@@ -0,0 +1,249 @@
+use crate::DgmResult;
+use serde::Deserialize;
+
+/// How confident a tool is that mechanically applying a [`Suggestion`] is safe, mirroring rustc's
+/// `Applicability` enum so `--error-format=json` output from rustc/clippy (or anything emitting
+/// the same diagnostic shape) maps onto this one-to-one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended; safe to apply without review.
+    MachineApplicable,
+    /// The suggestion may not be what the user intended; needs a human look before applying.
+    MaybeIncorrect,
+    /// The suggested code contains placeholders, e.g. `/* value */`, and can't be applied as-is.
+    HasPlaceholders,
+    /// The tool didn't report an applicability.
+    Unspecified,
+}
+
+impl Applicability {
+    fn parse(raw: &str) -> Self {
+        match raw {
+            "MachineApplicable" => Self::MachineApplicable,
+            "MaybeIncorrect" => Self::MaybeIncorrect,
+            "HasPlaceholders" => Self::HasPlaceholders,
+            _ => Self::Unspecified,
+        }
+    }
+}
+
+/// One span-level edit within a [`Suggestion`]: replace `file[byte_start..byte_end]` with
+/// `new_text`.
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub file: String,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub new_text: String,
+}
+
+/// One fixable diagnostic, as extracted by [`get_suggestions_from_json`]. Usually one
+/// [`Replacement`], but multi-span diagnostics (e.g. "remove these two unused imports") carry
+/// several that must all apply together.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,
+    pub applicability: Applicability,
+    pub replacements: Vec<Replacement>,
+}
+
+/// How aggressive a caller wants to be when applying a batch of [`Suggestion`]s via
+/// [`CodeFix::apply_all`] — auto-repairing trivially-fixable failures before re-running the agent
+/// should stick to [`Filter::MachineApplicableOnly`]; a human-reviewed pass can use
+/// [`Filter::Everything`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    MachineApplicableOnly,
+    Everything,
+}
+
+impl Filter {
+    fn accepts(self, applicability: Applicability) -> bool {
+        match self {
+            Self::MachineApplicableOnly => applicability == Applicability::MachineApplicable,
+            Self::Everything => true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    #[serde(default)]
+    spans: Vec<RawSpan>,
+    #[serde(default)]
+    children: Vec<RawDiagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSpan {
+    file_name: String,
+    byte_start: usize,
+    byte_end: usize,
+    #[serde(default)]
+    suggested_replacement: Option<String>,
+    #[serde(default)]
+    suggestion_applicability: Option<String>,
+}
+
+/// Parse `rustc`/`clippy` `--error-format=json` output (one JSON diagnostic object per line) into
+/// [`Suggestion`]s, keeping only spans that carry a `suggested_replacement`. Diagnostics nest
+/// their suggestions under `children` (this is how clippy reports most of its fixes), so those are
+/// walked recursively too. Lines that aren't a JSON object, or fail to parse, are skipped rather
+/// than treated as a hard error — compiler output is usually a mix of diagnostics and plain text.
+pub fn get_suggestions_from_json(text: &str) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            continue;
+        }
+        if let Ok(diagnostic) = serde_json::from_str::<RawDiagnostic>(line) {
+            collect_suggestions(&diagnostic, &mut suggestions);
+        }
+    }
+    suggestions
+}
+
+fn collect_suggestions(diagnostic: &RawDiagnostic, out: &mut Vec<Suggestion>) {
+    let replacements: Vec<Replacement> = diagnostic
+        .spans
+        .iter()
+        .filter_map(|span| {
+            span.suggested_replacement.as_ref().map(|new_text| Replacement {
+                file: span.file_name.clone(),
+                byte_start: span.byte_start,
+                byte_end: span.byte_end,
+                new_text: new_text.clone(),
+            })
+        })
+        .collect();
+
+    if !replacements.is_empty() {
+        let applicability = diagnostic
+            .spans
+            .iter()
+            .find_map(|span| span.suggestion_applicability.as_deref())
+            .map(Applicability::parse)
+            .unwrap_or(Applicability::Unspecified);
+
+        out.push(Suggestion {
+            message: diagnostic.message.clone(),
+            applicability,
+            replacements,
+        });
+    }
+
+    for child in &diagnostic.children {
+        collect_suggestions(child, out);
+    }
+}
+
+/// Mechanically applies [`Suggestion`]s to one file's text, rustfix-style: operates entirely on an
+/// in-memory string, leaving it to the caller to decide when (and whether) to write the result
+/// back to disk.
+pub struct CodeFix {
+    data: String,
+    applied_spans: Vec<(usize, usize)>,
+}
+
+impl CodeFix {
+    pub fn new(original: impl Into<String>) -> Self {
+        Self {
+            data: original.into(),
+            applied_spans: Vec::new(),
+        }
+    }
+
+    /// Apply every replacement in `suggestion`. See [`Self::apply_replacements`] for the splice
+    /// ordering and validation this delegates to.
+    pub fn apply(&mut self, suggestion: &Suggestion) -> DgmResult<()> {
+        self.apply_replacements(&suggestion.replacements)
+    }
+
+    /// Splice `replacements` into `self.data` in one pass: sorted by `byte_start` descending so
+    /// earlier (lower) byte offsets stay valid as later splices shift the string around them.
+    /// Errors out, rather than panicking, if any replacement's span is out of bounds or overlaps
+    /// a region this or a previous call already patched — applying both would silently corrupt
+    /// the file. Callers must gather every replacement they want applied *together* into one
+    /// call: splicing batch-by-batch against original-file offsets (one call per suggestion)
+    /// would have each splice shift the offsets the next batch was computed against.
+    fn apply_replacements(&mut self, replacements: &[Replacement]) -> DgmResult<()> {
+        let mut replacements = replacements.to_vec();
+        replacements.sort_by(|a, b| b.byte_start.cmp(&a.byte_start));
+
+        for (i, replacement) in replacements.iter().enumerate() {
+            if replacement.byte_start > replacement.byte_end || replacement.byte_end > self.data.len() {
+                anyhow::bail!(
+                    "suggestion span {}..{} is out of bounds for a {}-byte file",
+                    replacement.byte_start,
+                    replacement.byte_end,
+                    self.data.len()
+                );
+            }
+            if self
+                .applied_spans
+                .iter()
+                .any(|&(start, end)| replacement.byte_start < end && start < replacement.byte_end)
+            {
+                anyhow::bail!(
+                    "suggestion span {}..{} overlaps a region already patched by a previous suggestion",
+                    replacement.byte_start,
+                    replacement.byte_end
+                );
+            }
+            if replacements[..i]
+                .iter()
+                .any(|other| replacement.byte_start < other.byte_end && other.byte_start < replacement.byte_end)
+            {
+                anyhow::bail!(
+                    "suggestion span {}..{} overlaps another replacement in the same batch",
+                    replacement.byte_start,
+                    replacement.byte_end
+                );
+            }
+        }
+
+        for replacement in &replacements {
+            self.data.replace_range(replacement.byte_start..replacement.byte_end, &replacement.new_text);
+            self.applied_spans.push((replacement.byte_start, replacement.byte_end));
+        }
+
+        Ok(())
+    }
+
+    /// Apply every suggestion in `suggestions` that `filter` accepts, restricted to the
+    /// replacements that target `file` (a [`Suggestion`] spanning several files only has its
+    /// other-file replacements applied when `apply_all` is called again with that file's own
+    /// `CodeFix`). All accepted replacements are gathered up front and spliced in a single
+    /// [`Self::apply_replacements`] call (rustfix-style), rather than one call per suggestion,
+    /// so an earlier (lower-offset) suggestion's splice can't shift the offsets a later
+    /// suggestion was computed against. Returns how many suggestions were applied so a caller can
+    /// decide whether re-running the agent is even worth it.
+    pub fn apply_all(&mut self, suggestions: &[Suggestion], file: &str, filter: Filter) -> DgmResult<usize> {
+        let mut accepted = Vec::new();
+        let mut applied = 0;
+        for suggestion in suggestions {
+            if !filter.accepts(suggestion.applicability) {
+                continue;
+            }
+            let replacements: Vec<&Replacement> = suggestion.replacements.iter().filter(|r| r.file == file).collect();
+            if replacements.is_empty() {
+                continue;
+            }
+            accepted.extend(replacements.into_iter().cloned());
+            applied += 1;
+        }
+
+        if !accepted.is_empty() {
+            self.apply_replacements(&accepted)?;
+        }
+
+        Ok(applied)
+    }
+
+    /// Consume the fix, returning the patched file text to write back.
+    pub fn finish(self) -> String {
+        self.data
+    }
+}
@@ -0,0 +1,344 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+/// Reverse-dependency hops walked from a changed module before giving up on narrowing further.
+/// Without a cap, a change to a widely-imported core module (e.g. a package's `__init__.py`)
+/// would transitively pull in the entire test suite, defeating the point of selecting a subset.
+const MAX_TRANSITIVE_DEPTH: u32 = 6;
+
+/// Directory names never walked when building the module graph: VCS metadata, caches, and
+/// virtualenvs contain Python files that aren't part of the project's own dependency graph.
+const SKIP_DIRS: &[&str] = &[".git", "__pycache__", ".venv", "venv", "node_modules", ".mypy_cache", ".pytest_cache"];
+
+/// One test file selected by [`select_regression_tests`], with a node ID `pytest` can be invoked
+/// with directly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectedTest {
+    /// Path relative to the repo root, `/`-separated.
+    pub file: String,
+    /// Runnable `pytest` node ID. Since the import graph only resolves at module granularity,
+    /// this is currently always the whole file (`pytest <file>` runs every test it contains);
+    /// a future pass could narrow this to `file::test_name` by also parsing `def test_*`.
+    pub node_id: String,
+}
+
+/// Output of a successful static selection. [`AgenticSystem::get_regression_tests`] falls back to
+/// asking the LLM when this comes back empty (nothing in the diff mapped to a known module) or
+/// when [`select_regression_tests`] itself errors (e.g. the working tree isn't readable).
+#[derive(Debug, Clone, Default)]
+pub struct RegressionTestSelection {
+    pub tests: Vec<SelectedTest>,
+}
+
+impl RegressionTestSelection {
+    pub fn is_empty(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    /// Render as the same kind of free-text summary `get_regression_tests` would otherwise get
+    /// back from the LLM, so `run_regression_tests` doesn't need to care which path produced it.
+    pub fn render_summary(&self) -> String {
+        let mut out = String::from(
+            "Regression tests selected by static import-graph analysis (no LLM round-trip):\n\n",
+        );
+        for test in &self.tests {
+            out.push_str(&format!("- {}, run with `pytest {}`\n", test.file, test.node_id));
+        }
+        out
+    }
+}
+
+/// Static module dependency graph over the Python sources under a repo root, used to walk from a
+/// changed module to every test transitively importing it.
+struct ModuleGraph {
+    /// Dotted module name -> file path relative to root (`/`-separated).
+    module_files: HashMap<String, String>,
+    /// Dotted module name -> the modules importing it (the edges [`select_regression_tests`]
+    /// actually walks).
+    importers: HashMap<String, HashSet<String>>,
+    /// Every test file found, indexed by directory for the `conftest.py` special case.
+    test_trie: PathTrie,
+}
+
+/// Minimal prefix trie over `/`-separated test file paths, so every test nested under a
+/// `conftest.py`'s directory can be looked up in one prefix walk instead of re-scanning the full
+/// file list per conftest encountered in a diff.
+#[derive(Debug, Default)]
+struct PathTrie {
+    children: HashMap<String, PathTrie>,
+    files_here: Vec<String>,
+}
+
+impl PathTrie {
+    fn insert(&mut self, path: &str) {
+        let mut node = self;
+        let parts: Vec<&str> = path.split('/').collect();
+        for dir in &parts[..parts.len().saturating_sub(1)] {
+            node = node.children.entry((*dir).to_string()).or_default();
+        }
+        node.files_here.push(path.to_string());
+    }
+
+    /// All test files registered at or below `dir` (empty `dir` means the whole tree).
+    fn files_under(&self, dir: &str) -> Vec<String> {
+        let mut node = self;
+        if !dir.is_empty() {
+            for part in dir.split('/') {
+                match node.children.get(part) {
+                    Some(child) => node = child,
+                    None => return Vec::new(),
+                }
+            }
+        }
+        let mut out = Vec::new();
+        node.collect(&mut out);
+        out
+    }
+
+    fn collect(&self, out: &mut Vec<String>) {
+        out.extend(self.files_here.iter().cloned());
+        for child in self.children.values() {
+            child.collect(out);
+        }
+    }
+}
+
+fn is_test_path(rel_path: &str) -> bool {
+    let file_name = Path::new(rel_path).file_name().and_then(|f| f.to_str()).unwrap_or("");
+    file_name.starts_with("test_") || file_name.ends_with("_test.py") || rel_path.contains("/tests/") || rel_path.starts_with("tests/")
+}
+
+fn module_name_for(rel_path: &str) -> String {
+    let without_ext = rel_path.strip_suffix(".py").unwrap_or(rel_path);
+    if let Some(pkg) = without_ext.strip_suffix("/__init__") {
+        pkg.replace('/', ".")
+    } else {
+        without_ext.replace('/', ".")
+    }
+}
+
+/// The dotted package a module's relative (`from . import ...`) imports resolve against: a
+/// package's own dotted name for `__init__.py`, otherwise its parent directory's dotted name.
+fn containing_package(rel_path: &str) -> String {
+    if rel_path.ends_with("/__init__.py") || rel_path == "__init__.py" {
+        return module_name_for(rel_path);
+    }
+    match Path::new(rel_path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().replace('/', "."),
+        _ => String::new(),
+    }
+}
+
+impl ModuleGraph {
+    fn build(root: &Path) -> anyhow::Result<Self> {
+        let mut module_files = HashMap::new();
+        let mut test_trie = PathTrie::default();
+        let mut py_files = Vec::new();
+        walk_python_files(root, root, &mut py_files)?;
+
+        for rel_path in &py_files {
+            module_files.insert(module_name_for(rel_path), rel_path.clone());
+            if is_test_path(rel_path) {
+                test_trie.insert(rel_path);
+            }
+        }
+
+        let import_re = Regex::new(r"^\s*import\s+([\w.]+(?:\s*,\s*[\w.]+)*)").unwrap();
+        let from_re = Regex::new(r"^\s*from\s+(\.*)([\w.]*)\s+import\s+(.+)").unwrap();
+
+        let mut importers: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut add_edge = |imported: String, importer: String| {
+            importers.entry(imported).or_default().insert(importer);
+        };
+
+        for rel_path in &py_files {
+            let module = module_name_for(rel_path);
+            let Ok(content) = fs::read_to_string(root.join(rel_path)) else {
+                continue;
+            };
+            let package = containing_package(rel_path);
+
+            for line in content.lines() {
+                if let Some(caps) = from_re.captures(line) {
+                    let dots = caps[1].len();
+                    let rest = caps[2].trim();
+                    let names = caps[3].trim();
+
+                    let base = if dots == 0 {
+                        rest.to_string()
+                    } else {
+                        resolve_relative(&package, dots, rest)
+                    };
+
+                    for candidate in resolve_candidates(&module_files, &base, names) {
+                        add_edge(candidate, module.clone());
+                    }
+                } else if let Some(caps) = import_re.captures(line) {
+                    for raw in caps[1].split(',') {
+                        let dotted = raw.trim();
+                        if let Some(resolved) = resolve_dotted_prefix(&module_files, dotted) {
+                            add_edge(resolved, module.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            module_files,
+            importers,
+            test_trie,
+        })
+    }
+
+    fn module_for_file(&self, rel_path: &str) -> Option<String> {
+        let module = module_name_for(rel_path);
+        self.module_files.contains_key(&module).then_some(module)
+    }
+
+    fn is_test_module(&self, module: &str) -> bool {
+        self.module_files.get(module).map(|f| is_test_path(f)).unwrap_or(false)
+    }
+}
+
+/// Resolve `from <dots><rest> import ...` against the importing module's own package, e.g.
+/// `from . import foo` (dots=1) inside package `a.b` resolves to base `a.b`; `from .. import foo`
+/// (dots=2) resolves to base `a`.
+fn resolve_relative(package: &str, dots: usize, rest: &str) -> String {
+    let mut parts: Vec<&str> = if package.is_empty() { Vec::new() } else { package.split('.').collect() };
+    for _ in 1..dots {
+        parts.pop();
+    }
+    let base = parts.join(".");
+    if rest.is_empty() {
+        base
+    } else if base.is_empty() {
+        rest.to_string()
+    } else {
+        format!("{base}.{rest}")
+    }
+}
+
+/// `from <base> import <names>`: the imported names may themselves be submodules of `base`
+/// (`from pkg import submodule`) or plain attributes/functions defined in `base` itself. Try each
+/// name as a submodule of `base` first, then fall back to `base` so at least one edge is added
+/// whenever `base` is a known module in this repo.
+fn resolve_candidates(module_files: &HashMap<String, String>, base: &str, names: &str) -> Vec<String> {
+    let mut resolved = Vec::new();
+    for name in names.split(',') {
+        let name = name.trim().split_whitespace().next().unwrap_or("").trim_end_matches(',');
+        if name.is_empty() || name == "*" {
+            continue;
+        }
+        let submodule = if base.is_empty() { name.to_string() } else { format!("{base}.{name}") };
+        if module_files.contains_key(&submodule) {
+            resolved.push(submodule);
+        }
+    }
+    if resolved.is_empty() {
+        if let Some(m) = resolve_dotted_prefix(module_files, base) {
+            resolved.push(m);
+        }
+    }
+    resolved
+}
+
+/// An absolute `import a.b.c` may refer to a leaf module `a.b.c`, or `a.b.c` may just be an
+/// attribute of package `a.b` (or `a`) if `c` isn't itself a file in this repo. Walk up the
+/// dotted path until a known module is found, so the edge lands on the closest real file.
+fn resolve_dotted_prefix(module_files: &HashMap<String, String>, dotted: &str) -> Option<String> {
+    let mut parts: Vec<&str> = dotted.split('.').filter(|p| !p.is_empty()).collect();
+    while !parts.is_empty() {
+        let candidate = parts.join(".");
+        if module_files.contains_key(&candidate) {
+            return Some(candidate);
+        }
+        parts.pop();
+    }
+    None
+}
+
+fn walk_python_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> anyhow::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        if path.is_dir() {
+            if SKIP_DIRS.contains(&file_name.as_ref()) || file_name.starts_with('.') {
+                continue;
+            }
+            walk_python_files(root, &path, out)?;
+        } else if file_name.ends_with(".py") {
+            if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Walk the reverse-dependency edges from every file in `changed_files` (repo-root-relative,
+/// `/`-separated paths, as produced by a diff) to every test transitively importing it, capped at
+/// [`MAX_TRANSITIVE_DEPTH`] hops. `conftest.py` is treated as a dependency of every test in its
+/// directory subtree regardless of depth, since pytest applies its fixtures there implicitly
+/// rather than through an explicit import.
+pub fn select_regression_tests(root: &Path, changed_files: &[String]) -> anyhow::Result<RegressionTestSelection> {
+    let graph = ModuleGraph::build(root)?;
+    if graph.module_files.is_empty() {
+        return Ok(RegressionTestSelection::default());
+    }
+
+    let mut selected: HashSet<String> = HashSet::new();
+
+    for changed in changed_files {
+        let changed = changed.replace('\\', "/");
+
+        if Path::new(&changed).file_name().and_then(|f| f.to_str()) == Some("conftest.py") {
+            let dir = Path::new(&changed)
+                .parent()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            selected.extend(graph.test_trie.files_under(&dir));
+            continue;
+        }
+
+        let Some(start_module) = graph.module_for_file(&changed) else {
+            continue;
+        };
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((start_module.clone(), 0u32));
+        visited.insert(start_module);
+
+        while let Some((current, depth)) = queue.pop_front() {
+            if graph.is_test_module(&current) {
+                if let Some(file) = graph.module_files.get(&current) {
+                    selected.insert(file.clone());
+                }
+            }
+            if depth >= MAX_TRANSITIVE_DEPTH {
+                continue;
+            }
+            if let Some(importers) = graph.importers.get(&current) {
+                for importer in importers {
+                    if visited.insert(importer.clone()) {
+                        queue.push_back((importer.clone(), depth + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    let tests = selected.into_iter().map(|file| SelectedTest { node_id: file.clone(), file }).collect();
+
+    Ok(RegressionTestSelection { tests })
+}